@@ -0,0 +1,82 @@
+pub mod ber;
+pub mod eye;
+pub mod psd;
+pub mod sampler;
+pub mod stats;
+
+use crate::coder::SigElement;
+
+pub fn sample(sigs: &[SigElement], fs: f64) -> Box<[f64]> {
+    let Some(last) = sigs.last() else {
+        return Default::default();
+    };
+
+    let n = (last.tf() * fs).round() as usize;
+    let mut idx = 0;
+    (0..n)
+        .map(|k| {
+            let t = (k as f64 + 0.5) / fs;
+            while idx + 1 < sigs.len() && sigs[idx].tf() <= t {
+                idx += 1;
+            }
+            sigs[idx].lvl()
+        })
+        .collect()
+}
+
+// Corner points of the waveform as a step polyline, with both corners of
+// every level change at the same time.
+pub fn polyline(sigs: &[SigElement]) -> Box<[(f64, f64)]> {
+    let Some(first) = sigs.first() else {
+        return Default::default();
+    };
+
+    let mut points = Vec::with_capacity(sigs.len() * 3);
+    points.push((first.ti(), first.lvl()));
+    points.push((first.tf(), first.lvl()));
+    for win in sigs.windows(2) {
+        let a = win[0];
+        let b = win[1];
+
+        if (b.lvl() - a.lvl()).abs() > f64::EPSILON {
+            points.push((a.tf(), b.lvl()));
+        }
+        points.push((b.tf(), b.lvl()));
+    }
+
+    points.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{polyline, sample};
+    use crate::coder::SigElement;
+
+    #[test]
+    fn test_sample_holds_levels() {
+        let sigs = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 1.5, -1.0),
+            SigElement::new(1.5, 2.0, 0.0),
+        ];
+        assert_eq!(
+            sample(&sigs, 4.0).as_ref(),
+            &[1.0, 1.0, 1.0, 1.0, -1.0, -1.0, 0.0, 0.0]
+        );
+        assert!(sample(&[], 4.0).is_empty());
+    }
+
+    #[test]
+    fn test_polyline_steps_between_levels() {
+        let sigs = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, 1.0),
+            SigElement::new(2.0, 3.0, -1.0),
+        ];
+        assert_eq!(
+            polyline(&sigs).as_ref(),
+            &[(0.0, 1.0), (1.0, 1.0), (2.0, 1.0), (2.0, -1.0), (3.0, -1.0)]
+        );
+        assert!(polyline(&[]).is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+use super::sampler::Sampler;
+use crate::{
+    channel::Channel,
+    coder::{LineCoder, SigElement},
+    util::XorShift,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BerPoint {
+    snr_db: f64,
+    bits: usize,
+    errors: usize,
+}
+
+impl BerPoint {
+    #[inline]
+    pub fn snr_db(&self) -> f64 {
+        self.snr_db
+    }
+
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    #[inline]
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    #[inline]
+    pub fn ber(&self) -> f64 {
+        self.errors as f64 / self.bits.max(1) as f64
+    }
+}
+
+pub const SAMPLES_PER_BIT: usize = 8;
+
+// SNR is the ratio between the mean signal power and the noise variance of
+// each sample; the coder's own decoder acts as the threshold detector.
+pub fn sweep(
+    coder: &dyn LineCoder,
+    sampler: &Sampler,
+    n_bits: usize,
+    snrs_db: impl IntoIterator<Item = f64>,
+) -> Box<[BerPoint]> {
+    let bits = XorShift::default().bits(n_bits);
+    let fs = sampler.fs();
+    let ideal = sampler.sample(&coder.encode(&bits));
+    let power = ideal.iter().map(|x| x * x).sum::<f64>() / ideal.len().max(1) as f64;
+
+    snrs_db
+        .into_iter()
+        .map(|snr_db| {
+            let noise_rms = (power / 10f64.powf(snr_db / 10.0)).sqrt();
+            let rx = Channel::new().with_noise(noise_rms).apply(&ideal, fs);
+            let decoded = coder.decode(&to_sigs(&rx, fs));
+
+            // Block coders pad their last block, and the padding decodes to
+            // bits that were never sent; only missing bits count as errors.
+            let errors = bits.iter().zip(&decoded).filter(|(a, b)| a != b).count()
+                + bits.len().saturating_sub(decoded.len());
+            BerPoint {
+                snr_db,
+                bits: bits.len(),
+                errors,
+            }
+        })
+        .collect()
+}
+
+fn to_sigs(samples: &[f64], fs: f64) -> Box<[SigElement]> {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(k, &lvl)| SigElement::new(k as f64 / fs, (k + 1) as f64 / fs, lvl))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SAMPLES_PER_BIT, sweep};
+    use crate::analysis::sampler::{Sampler, Shaping};
+    use crate::coder::{
+        LineCoder, LineDecoder,
+        ami::Ami,
+        block::{BlockCoder, Composed, b4b5b::B4b5b, b8b10b::B8b10b},
+        nrz::Nrzl,
+    };
+
+    #[test]
+    fn test_ber_noiseless_is_error_free() {
+        for (coder, rate) in [
+            (Nrzl::new().boxed(), 1.0),
+            (Ami::new().with_bit_rate(1e3).boxed(), 1e3),
+            (
+                Composed::new(B4b5b::new().boxed(), Nrzl::new().boxed()).boxed(),
+                1.0,
+            ),
+        ] {
+            let sampler = Sampler::new()
+                .with_bit_rate(rate)
+                .with_samples_per_bit(SAMPLES_PER_BIT);
+            let points = sweep(coder.as_ref(), &sampler, 2000, [60.0]);
+            assert_eq!(points[0].errors(), 0);
+            assert_eq!(points[0].bits(), 2000);
+        }
+    }
+
+    #[test]
+    fn test_ber_noiseless_block_coded_ignores_padding() {
+        for block in [B4b5b::new().boxed(), B8b10b::new().boxed()] {
+            let coder = Composed::new(block, Nrzl::new().boxed());
+            let sampler = Sampler::new().with_samples_per_bit(SAMPLES_PER_BIT);
+            let points = sweep(&coder, &sampler, 2003, [60.0]);
+            assert!(coder.decode(&coder.encode(&[1; 2003])).len() > 2003);
+            assert_eq!(points[0].errors(), 0);
+        }
+    }
+
+    #[test]
+    fn test_ber_falls_with_snr() {
+        let sampler = Sampler::new().with_samples_per_bit(SAMPLES_PER_BIT);
+        let points = sweep(&Nrzl::new(), &sampler, 20_000, [0.0, 4.0, 8.0]);
+        assert!(points.windows(2).all(|w| w[0].ber() > w[1].ber()));
+
+        // A single sample at 0 dB gives Q(1) ≈ 0.159 for polar NRZ.
+        assert!(
+            (points[0].ber() - 0.159).abs() < 0.01,
+            "{}",
+            points[0].ber()
+        );
+    }
+
+    #[test]
+    fn test_ber_noiseless_with_shaped_pulses() {
+        for shaping in [Shaping::Ramp(0.3), Shaping::RaisedCosine(0.5)] {
+            let sampler = Sampler::new()
+                .with_samples_per_bit(SAMPLES_PER_BIT)
+                .with_shaping(shaping);
+            let points = sweep(&Ami::new(), &sampler, 2000, [60.0]);
+            assert_eq!(points[0].errors(), 0, "{shaping:?}");
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use super::sampler::Sampler;
+use crate::{channel::Channel, coder::SigElement};
+
+pub type Trace = Box<[(f64, f64)]>;
+
+// Eye of `sigs` sent through `channel`. The held levels classify the samples,
+// so pulse shaping only changes the traces and adds no thresholds.
+pub fn eye(sigs: &[SigElement], sampler: &Sampler, channel: &Channel, span: usize) -> Eye {
+    let fs = sampler.fs();
+    let ideal = super::sample(sigs, fs);
+    let rx = channel.apply(&sampler.sample(sigs), fs);
+    Eye::new(
+        &ideal,
+        &rx,
+        sampler.samples_per_bit().round() as usize,
+        span,
+    )
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Eye {
+    span: usize,
+    traces: Box<[Trace]>,
+    height: f64,
+    width: f64,
+}
+
+impl Eye {
+    pub const MAX_TRACES: usize = 128;
+
+    // `ideal` classifies each received sample by the level it should have
+    // had, so the opening is measured between the levels of every threshold.
+    pub fn new(ideal: &[f64], rx: &[f64], spb: usize, span: usize) -> Self {
+        let len = span * spb;
+        let traces = (0..)
+            .map(|k| k * spb)
+            .take_while(|&start| start + len < rx.len())
+            .take(Self::MAX_TRACES)
+            .map(|start| {
+                (0..=len)
+                    .map(|i| (i as f64 / spb as f64, rx[start + i]))
+                    .collect()
+            })
+            .collect();
+
+        let mut levels = ideal.to_vec();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        let thresholds = levels
+            .windows(2)
+            .map(|w| (w[0] + w[1]) / 2.0)
+            .collect::<Box<[_]>>();
+
+        let openings = (0..spb)
+            .map(|p| {
+                thresholds
+                    .iter()
+                    .filter_map(|&thr| {
+                        let mut upper = f64::INFINITY;
+                        let mut lower = f64::NEG_INFINITY;
+                        for (&want, &got) in ideal.iter().zip(rx).skip(p).step_by(spb) {
+                            if want > thr {
+                                upper = upper.min(got);
+                            } else {
+                                lower = lower.max(got);
+                            }
+                        }
+                        (upper.is_finite() && lower.is_finite()).then_some(upper - lower)
+                    })
+                    .reduce(f64::min)
+                    .unwrap_or(f64::NEG_INFINITY)
+            })
+            .collect::<Box<[_]>>();
+
+        let height = openings.iter().copied().fold(0.0, f64::max);
+        let width = openings.iter().filter(|&&o| o > 0.0).count() as f64 / spb.max(1) as f64;
+
+        Self {
+            span,
+            traces,
+            height,
+            width,
+        }
+    }
+
+    #[inline]
+    pub fn span(&self) -> usize {
+        self.span
+    }
+
+    #[inline]
+    pub fn traces(&self) -> &[Trace] {
+        &self.traces
+    }
+
+    #[inline]
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Eye;
+    use crate::{
+        analysis::{
+            self,
+            sampler::{Sampler, Shaping},
+        },
+        channel::{Channel, LowPass},
+        coder::{LineCoder, ami::Ami, nrz::Nrzl},
+        util::XorShift,
+    };
+
+    const SPB: usize = 16;
+
+    #[test]
+    fn test_eye_ideal_is_fully_open() {
+        let bits = XorShift::default().bits(512);
+        for sigs in [Nrzl::new().encode(&bits), Ami::new().encode(&bits)] {
+            let ideal = analysis::sample(&sigs, SPB as f64);
+            let eye = Eye::new(&ideal, &ideal, SPB, 2);
+            assert_eq!(eye.width(), 1.0);
+            assert!(eye.height() > 0.99 && eye.height() < 2.01);
+            assert_eq!(eye.traces().len(), Eye::MAX_TRACES);
+            assert_eq!(eye.traces()[0].len(), 2 * SPB + 1);
+        }
+    }
+
+    #[test]
+    fn test_eye_closes_with_bandwidth() {
+        let bits = XorShift::default().bits(512);
+        let ideal = analysis::sample(&Nrzl::new().encode(&bits), SPB as f64);
+
+        let mut last = (2.0, 1.0);
+        for cutoff in [2.0, 0.5, 0.2] {
+            let rx = LowPass::new(cutoff).apply(&ideal, SPB as f64);
+            let eye = Eye::new(&ideal, &rx, SPB, 1);
+            assert!(eye.height() < last.0 && eye.width() <= last.1);
+            last = (eye.height(), eye.width());
+        }
+    }
+
+    #[test]
+    fn test_eye_shaped_stays_fast() {
+        let sampler = Sampler::new()
+            .with_samples_per_bit(SPB)
+            .with_shaping(Shaping::RaisedCosine(0.35));
+        let sigs = Ami::new().encode(&XorShift::default().bits(8192));
+        let held = analysis::sample(&sigs, sampler.fs());
+        let shaped = sampler.sample(&sigs);
+
+        // Shaped levels would each be a threshold, scaling with the square
+        // of the bit count.
+        let start = Instant::now();
+        let eye = Eye::new(&held, &shaped, SPB, 2);
+        assert!(
+            start.elapsed() < Duration::from_secs(1),
+            "{:?}",
+            start.elapsed()
+        );
+        assert!(eye.height() > 0.25 && eye.width() > 0.25);
+
+        let sigs = &sigs[..256];
+        let held = analysis::sample(sigs, sampler.fs());
+        assert_eq!(
+            super::eye(sigs, &sampler, &Channel::new(), 2),
+            Eye::new(&held, &sampler.sample(sigs), SPB, 2)
+        );
+    }
+}
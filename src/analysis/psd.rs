@@ -0,0 +1,191 @@
+use std::f64::consts::PI;
+
+use super::sampler::Sampler;
+use crate::coder::SigElement;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Spectrum {
+    freqs: Box<[f64]>,
+    density: Box<[f64]>,
+}
+
+impl Spectrum {
+    #[inline]
+    pub fn freqs(&self) -> &[f64] {
+        &self.freqs
+    }
+
+    #[inline]
+    pub fn density(&self) -> &[f64] {
+        &self.density
+    }
+
+    pub fn power(&self) -> f64 {
+        let df = self.freqs.get(1).copied().unwrap_or_default();
+        self.density.iter().sum::<f64>() * df
+    }
+}
+
+pub const SAMPLES_PER_BIT: usize = 16;
+pub const SEGMENT_LEN: usize = 512;
+
+pub fn psd(sigs: &[SigElement], sampler: &Sampler) -> Spectrum {
+    let fs = sampler.fs();
+    welch(&sampler.sample(sigs), fs, SEGMENT_LEN)
+}
+
+// Welch's method: averaged periodograms of Hann windowed, half overlapping
+// segments, scaled to a one-sided density.
+pub fn welch(samples: &[f64], fs: f64, seg_len: usize) -> Spectrum {
+    if samples.is_empty() {
+        return Default::default();
+    }
+
+    let seg_len = seg_len.min(samples.len().next_power_of_two()).max(2);
+    let window = (0..seg_len)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / seg_len as f64).cos())
+        .collect::<Box<[_]>>();
+    let scale = fs * window.iter().map(|w| w * w).sum::<f64>();
+
+    let hop = seg_len / 2;
+    let n_bins = seg_len / 2 + 1;
+    let mut acc = vec![0.0; n_bins];
+    let mut n_segs = 0;
+    let mut buf = vec![(0.0, 0.0); seg_len];
+    let mut start = 0;
+    loop {
+        for (i, (slot, w)) in buf.iter_mut().zip(&window).enumerate() {
+            let x = samples.get(start + i).copied().unwrap_or_default();
+            *slot = (x * w, 0.0);
+        }
+        fft(&mut buf);
+        for (k, slot) in acc.iter_mut().enumerate() {
+            let (re, im) = buf[k];
+            *slot += re * re + im * im;
+        }
+        n_segs += 1;
+
+        start += hop;
+        if start + seg_len > samples.len() {
+            break;
+        }
+    }
+
+    let density = acc
+        .into_iter()
+        .enumerate()
+        .map(|(k, p)| {
+            let one_sided = if k == 0 || k == n_bins - 1 { 1.0 } else { 2.0 };
+            one_sided * p / (scale * n_segs as f64)
+        })
+        .collect();
+    let freqs = (0..n_bins)
+        .map(|k| k as f64 * fs / seg_len as f64)
+        .collect();
+
+    Spectrum { freqs, density }
+}
+
+fn fft(buf: &mut [(f64, f64)]) {
+    let n = buf.len();
+    debug_assert!(n.is_power_of_two());
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let ang = -2.0 * PI / len as f64;
+        for chunk in buf.chunks_exact_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            for (k, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                let (wr, wi) = ((ang * k as f64).cos(), (ang * k as f64).sin());
+                let t = (b.0 * wr - b.1 * wi, b.0 * wi + b.1 * wr);
+                *b = (a.0 - t.0, a.1 - t.1);
+                *a = (a.0 + t.0, a.1 + t.1);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SAMPLES_PER_BIT, Spectrum, fft, psd, welch};
+    use crate::{
+        analysis::sampler::{Sampler, Shaping},
+        coder::{LineCoder, ami::Ami, manch::Manchester, nrz::Nrzl},
+        util::XorShift,
+    };
+
+    fn density_at(spec: &Spectrum, f: f64) -> f64 {
+        let idx = spec.freqs().partition_point(|&x| x < f);
+        spec.density()[idx.min(spec.density().len() - 1)]
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        let input = [1.0, 2.0, 0.0, -1.0, 0.5, 0.0, 3.0, -2.0];
+        let mut buf = input.map(|x| (x, 0.0));
+        fft(&mut buf);
+
+        for (k, &(re, im)) in buf.iter().enumerate() {
+            let (mut er, mut ei) = (0.0, 0.0);
+            for (n, x) in input.iter().enumerate() {
+                let ang = -2.0 * std::f64::consts::PI * (k * n) as f64 / 8.0;
+                er += x * ang.cos();
+                ei += x * ang.sin();
+            }
+            assert!((re - er).abs() < 1e-9 && (im - ei).abs() < 1e-9, "bin {k}");
+        }
+    }
+
+    #[test]
+    fn test_welch_preserves_power() {
+        let samples = (0..4096)
+            .map(|i| (i as f64 * 0.3).sin())
+            .collect::<Box<[_]>>();
+        let spec = welch(&samples, 1.0, 256);
+        assert!((spec.power() - 0.5).abs() < 0.02, "{}", spec.power());
+    }
+
+    #[test]
+    fn test_psd_spectral_shapes() {
+        let bits = XorShift::default().bits(4096);
+        let sampler = Sampler::new().with_samples_per_bit(SAMPLES_PER_BIT);
+
+        let nrz = psd(&Nrzl::new().encode(&bits), &sampler);
+        assert!((nrz.power() - 1.0).abs() < 0.05);
+        assert!(density_at(&nrz, 0.0) > 10.0 * density_at(&nrz, 1.0));
+
+        for spec in [
+            psd(&Manchester::new().encode(&bits), &sampler),
+            psd(&Ami::new().encode(&bits), &sampler),
+        ] {
+            let peak = spec.density().iter().copied().fold(0.0, f64::max);
+            assert!(density_at(&spec, 0.0) < 0.05 * peak);
+        }
+    }
+
+    #[test]
+    fn test_psd_raised_cosine_limits_bandwidth() {
+        let bits = XorShift::default().bits(4096);
+        let sigs = Nrzl::new().encode(&bits);
+        let sampler = Sampler::new().with_samples_per_bit(SAMPLES_PER_BIT);
+
+        let square = psd(&sigs, &sampler);
+        let shaped = psd(&sigs, &sampler.with_shaping(Shaping::RaisedCosine(0.5)));
+        assert!(density_at(&shaped, 1.5) < 0.01 * density_at(&square, 1.5));
+        assert!(shaped.power() > 0.5 * square.power(), "{}", shaped.power());
+    }
+}
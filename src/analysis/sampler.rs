@@ -0,0 +1,284 @@
+use std::{f64::consts::PI, str::FromStr};
+
+use crate::coder::SigElement;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Shaping {
+    #[default]
+    None,
+    // Linear edges lasting the given fraction of the bit time.
+    Ramp(f64),
+    // Raised-cosine filter at the bit rate with the given roll-off.
+    RaisedCosine(f64),
+}
+
+// Written as `none`, `ramp:<fraction>` or `rc:<roll-off>`.
+impl FromStr for Shaping {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.trim();
+        if src.eq_ignore_ascii_case("none") {
+            return Ok(Shaping::None);
+        }
+
+        let (kind, val) = src
+            .split_once(':')
+            .ok_or_else(|| format!("unknown pulse shaping `{src}`"))?;
+        let val = val
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid pulse shaping parameter `{}`", val.trim()))?;
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "ramp" if val > 0.0 && val <= 1.0 => Ok(Shaping::Ramp(val)),
+            "ramp" => Err(format!("rise time must be within (0, 1] bits, got {val}")),
+            "rc" if (0.0..=1.0).contains(&val) => Ok(Shaping::RaisedCosine(val)),
+            "rc" => Err(format!("roll-off must be within [0, 1], got {val}")),
+            kind => Err(format!("unknown pulse shaping `{kind}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampler {
+    bit_rate: f64,
+    spb: usize,
+    fs: Option<f64>,
+    shaping: Shaping,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self {
+            bit_rate: 1.0,
+            spb: Self::DEFAULT_SAMPLES_PER_BIT,
+            fs: None,
+            shaping: Shaping::None,
+        }
+    }
+}
+
+impl Sampler {
+    pub const DEFAULT_SAMPLES_PER_BIT: usize = 16;
+    // Half-length of the raised-cosine response, in bits.
+    const RC_SPAN: usize = 4;
+
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.bit_rate = rate;
+        self
+    }
+
+    #[inline]
+    pub fn with_samples_per_bit(mut self, spb: usize) -> Self {
+        self.spb = spb.max(1);
+        self
+    }
+
+    // Samples at a fixed rate instead of a whole number of samples per bit,
+    // as audio output does.
+    #[inline]
+    pub fn with_sample_rate(mut self, fs: f64) -> Self {
+        self.fs = Some(fs);
+        self
+    }
+
+    #[inline]
+    pub fn with_shaping(mut self, shaping: Shaping) -> Self {
+        self.shaping = shaping;
+        self
+    }
+
+    #[inline]
+    pub fn samples_per_bit(&self) -> f64 {
+        self.fs() / self.bit_rate
+    }
+
+    #[inline]
+    pub fn shaping(&self) -> Shaping {
+        self.shaping
+    }
+
+    #[inline]
+    pub fn fs(&self) -> f64 {
+        self.fs.unwrap_or(self.bit_rate * self.spb as f64)
+    }
+
+    // One sample in the middle of every 1/fs slot, the waveform holding its
+    // first and last levels beyond its ends.
+    pub fn sample(&self, sigs: &[SigElement]) -> Vec<f64> {
+        let tb = self.bit_rate.recip();
+        match self.shaping {
+            Shaping::Ramp(rise) if rise > 0.0 => self.ramp(sigs, rise * tb),
+            Shaping::RaisedCosine(beta) => {
+                let held = super::sample(sigs, self.fs());
+                self.raised_cosine(&held, beta.clamp(0.0, 1.0))
+            }
+            _ => super::sample(sigs, self.fs()).into_vec(),
+        }
+    }
+
+    // Averaging over a window as wide as the rise time turns every step into
+    // a linear edge of that length.
+    fn ramp(&self, sigs: &[SigElement], width: f64) -> Vec<f64> {
+        let (Some(first), Some(last)) = (sigs.first(), sigs.last()) else {
+            return Vec::new();
+        };
+
+        let mut areas = Vec::with_capacity(sigs.len() + 1);
+        areas.push(0.0);
+        for sig in sigs {
+            areas.push(areas[areas.len() - 1] + (sig.tf() - sig.ti()) * sig.lvl());
+        }
+        let area_to = |t: f64| {
+            if t <= first.ti() {
+                return (t - first.ti()) * first.lvl();
+            }
+            if t >= last.tf() {
+                return areas[sigs.len()] + (t - last.tf()) * last.lvl();
+            }
+            let idx = sigs.partition_point(|sig| sig.tf() <= t);
+            areas[idx] + (t - sigs[idx].ti()) * sigs[idx].lvl()
+        };
+
+        let fs = self.fs();
+        let n = (last.tf() * fs).round() as usize;
+        (0..n)
+            .map(|k| {
+                let t = (k as f64 + 0.5) / fs;
+                (area_to(t + width / 2.0) - area_to(t - width / 2.0)) / width
+            })
+            .collect()
+    }
+
+    fn raised_cosine(&self, held: &[f64], beta: f64) -> Vec<f64> {
+        let (Some(&first), Some(&last)) = (held.first(), held.last()) else {
+            return Vec::new();
+        };
+
+        let spb = self.samples_per_bit();
+        let half = (Self::RC_SPAN as f64 * spb).round() as isize;
+        let taps = (-half..=half)
+            .map(|j| {
+                let x = j as f64 / spb;
+                let sinc = |x: f64| {
+                    if x == 0.0 {
+                        1.0
+                    } else {
+                        (PI * x).sin() / (PI * x)
+                    }
+                };
+                let den = 1.0 - (2.0 * beta * x).powi(2);
+                if den.abs() < 1e-9 {
+                    PI / 4.0 * sinc(1.0 / (2.0 * beta))
+                } else {
+                    sinc(x) * (PI * beta * x).cos() / den
+                }
+            })
+            .collect::<Box<[_]>>();
+        let gain = taps.iter().sum::<f64>();
+
+        let at = |k: isize| match usize::try_from(k) {
+            Ok(k) => held.get(k).copied().unwrap_or(last),
+            Err(_) => first,
+        };
+        (0..held.len() as isize)
+            .map(|k| {
+                taps.iter()
+                    .zip(-half..)
+                    .map(|(h, j)| h * at(k - j))
+                    .sum::<f64>()
+                    / gain
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sampler, Shaping};
+    use crate::coder::{LineCoder, SigElement, nrz::Nrzl};
+
+    #[test]
+    fn test_sampler_holds_levels_without_shaping() {
+        let sigs = [
+            SigElement::new(0.0, 0.5, 1.0),
+            SigElement::new(0.5, 1.0, -1.0),
+        ];
+        let sampler = Sampler::new().with_bit_rate(2.0).with_samples_per_bit(2);
+        assert_eq!(sampler.fs(), 4.0);
+        assert_eq!(sampler.sample(&sigs), [1.0, 1.0, -1.0, -1.0]);
+        assert!(sampler.sample(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_sampler_ramps_edges_linearly() {
+        let sigs = [
+            SigElement::new(0.0, 1.0, -1.0),
+            SigElement::new(1.0, 2.0, 1.0),
+        ];
+        let out = Sampler::new()
+            .with_samples_per_bit(8)
+            .with_shaping(Shaping::Ramp(0.5))
+            .sample(&sigs);
+
+        let expected = [-1.0, -1.0, -1.0, -1.0, -1.0, -1.0, -0.75, -0.25, 0.25, 0.75];
+        for (got, want) in out.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-12, "{out:?}");
+        }
+        assert!(
+            out[10..].iter().all(|&y| (y - 1.0).abs() < 1e-12),
+            "{out:?}"
+        );
+    }
+
+    #[test]
+    fn test_sampler_raised_cosine_keeps_levels_between_edges() {
+        let bits = [1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let sigs = Nrzl::new().encode(&bits);
+        for beta in [0.25, 0.35, 0.5, 1.0] {
+            let out = Sampler::new()
+                .with_samples_per_bit(8)
+                .with_shaping(Shaping::RaisedCosine(beta))
+                .sample(&sigs);
+
+            assert_eq!(out.len(), bits.len() * 8);
+            assert!((out[4] - 1.0).abs() < 0.05, "{beta}: {}", out[4]);
+            assert!((out[out.len() - 4] + 1.0).abs() < 0.05, "{beta}");
+            assert!(out[68] > 0.0 && out[76] < 0.0, "{beta}");
+            assert!(out.iter().all(|y| y.abs() < 1.2), "{beta}: {out:?}");
+        }
+    }
+
+    #[test]
+    fn test_shaping_from_str() {
+        assert_eq!("none".parse(), Ok(Shaping::None));
+        assert_eq!("ramp:0.2".parse(), Ok(Shaping::Ramp(0.2)));
+        assert_eq!("RC: 0.35".parse(), Ok(Shaping::RaisedCosine(0.35)));
+        for src in ["", "ramp", "ramp:0", "rc:1.5", "rc:x", "gauss:0.5"] {
+            assert!(src.parse::<Shaping>().is_err(), "{src:?}");
+        }
+    }
+
+    #[test]
+    fn test_sampler_fixed_sample_rate() {
+        let bits = [1, 1, 1, 1, 0, 0, 0, 0];
+        let sigs = Nrzl::new().with_bit_rate(1200.0).encode(&bits);
+        let sampler = Sampler::new()
+            .with_bit_rate(1200.0)
+            .with_sample_rate(44_100.0);
+        assert_eq!(sampler.samples_per_bit(), 36.75);
+        assert_eq!(sampler.sample(&sigs).len(), 294);
+
+        let shaped = sampler
+            .with_shaping(Shaping::RaisedCosine(0.5))
+            .sample(&sigs);
+        assert_eq!(shaped.len(), 294);
+        assert!(shaped[73] > 0.9 && shaped[220] < -0.9, "{shaped:?}");
+    }
+}
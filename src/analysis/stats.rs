@@ -0,0 +1,155 @@
+use crate::coder::SigElement;
+
+// Run lengths and the running digital sum are measured in bit periods, so
+// `rds` reads as volt-bits and stays comparable across bit rates.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Stats {
+    mean: f64,
+    rds: (f64, f64),
+    transitions: f64,
+    longest_run: f64,
+    levels: usize,
+    baud: f64,
+    bit_rate: f64,
+}
+
+impl Stats {
+    pub fn new(sigs: &[SigElement], bit_rate: f64) -> Self {
+        let (Some(first), Some(last)) = (sigs.first(), sigs.last()) else {
+            return Self {
+                bit_rate,
+                ..Default::default()
+            };
+        };
+
+        let duration = last.tf() - first.ti();
+        if duration <= 0.0 {
+            return Self {
+                bit_rate,
+                ..Default::default()
+            };
+        }
+        let bits = duration * bit_rate;
+
+        let mut area = 0.0_f64;
+        let mut rds = (0.0_f64, 0.0_f64);
+        for sig in sigs {
+            area += sig.lvl() * (sig.tf() - sig.ti());
+            let sum = area * bit_rate;
+            rds = (rds.0.min(sum), rds.1.max(sum));
+        }
+
+        let mut transitions = 0;
+        let mut run = first.tf() - first.ti();
+        let mut longest_run = run;
+        for win in sigs.windows(2) {
+            if (win[1].lvl() - win[0].lvl()).abs() > 1e-9 {
+                transitions += 1;
+                run = 0.0;
+            }
+            run += win[1].tf() - win[1].ti();
+            longest_run = longest_run.max(run);
+        }
+
+        let mut levels = sigs.iter().map(|s| s.lvl()).collect::<Vec<_>>();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+
+        Self {
+            mean: area / duration,
+            rds,
+            transitions: transitions as f64 / bits,
+            longest_run: longest_run * bit_rate,
+            levels: levels.len(),
+            baud: sigs.len() as f64 / duration,
+            bit_rate,
+        }
+    }
+
+    #[inline]
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    #[inline]
+    pub fn rds(&self) -> (f64, f64) {
+        self.rds
+    }
+
+    #[inline]
+    pub fn transitions_per_bit(&self) -> f64 {
+        self.transitions
+    }
+
+    #[inline]
+    pub fn longest_run(&self) -> f64 {
+        self.longest_run
+    }
+
+    #[inline]
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    #[inline]
+    pub fn baud(&self) -> f64 {
+        self.baud
+    }
+
+    #[inline]
+    pub fn baud_ratio(&self) -> f64 {
+        if self.bit_rate > 0.0 {
+            self.baud / self.bit_rate
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use crate::coder::{LineCoder, ami::Ami, manch::Manchester, nrz::Nrzl};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_stats_nrzl() {
+        let bits = [1, 1, 1, 0, 1, 0, 0, 0];
+        let stats = Stats::new(&Nrzl::new().encode(&bits), 1.0);
+
+        assert!(close(stats.mean(), 0.0));
+        assert!(close(stats.rds().0, 0.0) && close(stats.rds().1, 3.0));
+        assert!(close(stats.transitions_per_bit(), 3.0 / 8.0));
+        assert!(close(stats.longest_run(), 3.0));
+        assert_eq!(stats.levels(), 2);
+        assert!(close(stats.baud_ratio(), 1.0));
+    }
+
+    #[test]
+    fn test_stats_manchester_and_ami() {
+        let bits = [0, 0, 0, 0, 1, 1];
+
+        let manch = Stats::new(&Manchester::new().encode(&bits), 1.0);
+        assert!(close(manch.mean(), 0.0));
+        assert!(close(manch.longest_run(), 1.0));
+        assert!(close(manch.baud_ratio(), 2.0));
+        assert_eq!(manch.levels(), 2);
+
+        let ami = Stats::new(&Ami::new().encode(&bits), 1.0);
+        assert!(close(ami.mean(), 0.0));
+        assert!(close(ami.longest_run(), 4.0));
+        assert!(close(ami.transitions_per_bit(), 2.0 / 6.0));
+        assert_eq!(ami.levels(), 3);
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let stats = Stats::new(&[], 2.0);
+        assert_eq!(stats.levels(), 0);
+        assert!(close(stats.mean(), 0.0));
+        assert!(close(stats.baud_ratio(), 0.0));
+    }
+}
@@ -1,3 +1,5 @@
+use lcv::coder::registry::Registry;
+
 use crate::{
     term::{Event, Terminal},
     tui::Tui,
@@ -10,10 +12,10 @@ pub struct App {
 }
 
 impl App {
-    pub fn build() -> anyhow::Result<Self> {
+    pub fn build(registry: Registry) -> anyhow::Result<Self> {
         Ok(Self {
             term: Terminal::new(),
-            tui: Tui::build()?,
+            tui: Tui::build(registry)?,
         })
     }
 
@@ -0,0 +1,230 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use crate::util::XorShift;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOrder {
+    #[default]
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LowPass {
+    cutoff: f64,
+    order: FilterOrder,
+}
+
+impl LowPass {
+    #[inline]
+    pub fn new(cutoff: f64) -> Self {
+        Self {
+            cutoff,
+            order: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_order(mut self, order: FilterOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn apply(&self, samples: &[f64], fs: f64) -> Box<[f64]> {
+        let x0 = samples.first().copied().unwrap_or_default();
+        match self.order {
+            FilterOrder::First => {
+                let alpha = 1.0 - (-2.0 * PI * self.cutoff / fs).exp();
+                let mut y = x0;
+                samples
+                    .iter()
+                    .map(|&x| {
+                        y += alpha * (x - y);
+                        y
+                    })
+                    .collect()
+            }
+            FilterOrder::Second => {
+                // Butterworth biquad from the bilinear transform, prewarped
+                // at the cutoff.
+                let k = (PI * self.cutoff.min(0.45 * fs) / fs).tan();
+                let norm = (1.0 + SQRT_2 * k + k * k).recip();
+                let b0 = k * k * norm;
+                let a1 = 2.0 * (k * k - 1.0) * norm;
+                let a2 = (1.0 - SQRT_2 * k + k * k) * norm;
+
+                let (mut x1, mut x2, mut y1, mut y2) = (x0, x0, x0, x0);
+                samples
+                    .iter()
+                    .map(|&x| {
+                        let y = b0 * (x + 2.0 * x1 + x2) - a1 * y1 - a2 * y2;
+                        (x2, x1, y2, y1) = (x1, x, y1, y);
+                        y
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Channel {
+    attenuation: f64,
+    low_pass: Option<LowPass>,
+    dc_block: Option<f64>,
+    noise_rms: f64,
+    seed: u64,
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self {
+            attenuation: 0.0,
+            low_pass: None,
+            dc_block: None,
+            noise_rms: 0.0,
+            seed: XorShift::DEFAULT_SEED,
+        }
+    }
+}
+
+impl Channel {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_attenuation(mut self, db: f64) -> Self {
+        self.attenuation = db;
+        self
+    }
+
+    #[inline]
+    pub fn with_low_pass(mut self, low_pass: LowPass) -> Self {
+        self.low_pass = Some(low_pass);
+        self
+    }
+
+    #[inline]
+    pub fn with_dc_block(mut self, cutoff: f64) -> Self {
+        self.dc_block = Some(cutoff);
+        self
+    }
+
+    #[inline]
+    pub fn with_noise(mut self, rms: f64) -> Self {
+        self.noise_rms = rms.abs();
+        self
+    }
+
+    #[inline]
+    pub fn is_ideal(&self) -> bool {
+        self.attenuation == 0.0
+            && self.low_pass.is_none()
+            && self.dc_block.is_none()
+            && self.noise_rms == 0.0
+    }
+
+    pub fn apply(&self, samples: &[f64], fs: f64) -> Box<[f64]> {
+        let gain = 10f64.powf(-self.attenuation / 20.0);
+        let mut out = samples.iter().map(|x| x * gain).collect::<Box<[_]>>();
+
+        if let Some(low_pass) = self.low_pass {
+            out = low_pass.apply(&out, fs);
+        }
+
+        if let Some(cutoff) = self.dc_block {
+            // AC coupling through a series capacitor, discharged at t = 0.
+            let rc = (2.0 * PI * cutoff).recip();
+            let a = rc / (rc + fs.recip());
+            let (mut x1, mut y1) = (0.0, 0.0);
+            for x in out.iter_mut() {
+                y1 = a * (y1 + *x - x1);
+                x1 = *x;
+                *x = y1;
+            }
+        }
+
+        if self.noise_rms > 0.0 {
+            let mut rng = XorShift::new(self.seed);
+            for x in out.iter_mut() {
+                *x += self.noise_rms * gaussian(&mut rng);
+            }
+        }
+
+        out
+    }
+}
+
+fn gaussian(rng: &mut XorShift) -> f64 {
+    // Box-Muller; 1 - u keeps the logarithm finite.
+    let u1 = 1.0 - rng.next_f64();
+    let u2 = rng.next_f64();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Channel, FilterOrder, LowPass};
+
+    fn step(len: usize) -> Vec<f64> {
+        let mut step = vec![0.0; len];
+        step[1..].fill(1.0);
+        step
+    }
+
+    #[test]
+    fn test_low_pass_step_response() {
+        let fs = 1000.0;
+        let out = LowPass::new(10.0).apply(&step(2000), fs);
+        // One time constant (1 / 2πfc) reaches 1 - 1/e of the step.
+        let tau = (fs / (2.0 * std::f64::consts::PI * 10.0)).round() as usize;
+        assert!((out[tau] - (1.0 - (-1.0f64).exp())).abs() < 0.02);
+        assert!((out[1999] - 1.0).abs() < 1e-6);
+        assert_eq!(out[0], 0.0);
+    }
+
+    #[test]
+    fn test_second_order_low_pass_overshoots() {
+        let out = LowPass::new(10.0)
+            .with_order(FilterOrder::Second)
+            .apply(&step(2000), 1000.0);
+        let peak = out.iter().copied().fold(0.0, f64::max);
+        // A Butterworth response overshoots by about 4.3 %.
+        assert!(peak > 1.03 && peak < 1.06, "{peak}");
+        assert!((out[1999] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_channel_ideal_is_identity() {
+        let input = step(64);
+        let channel = Channel::new();
+        assert!(channel.is_ideal());
+        assert_eq!(channel.apply(&input, 16.0).as_ref(), input.as_slice());
+    }
+
+    #[test]
+    fn test_channel_attenuation_and_dc_block() {
+        let input = vec![1.0; 4000];
+        let out = Channel::new().with_attenuation(20.0).apply(&input, 1000.0);
+        assert!(out.iter().all(|&x| (x - 0.1).abs() < 1e-12));
+
+        let out = Channel::new().with_dc_block(5.0).apply(&input, 1000.0);
+        assert!((out[0] - 1.0).abs() < 0.05);
+        assert!(out[3999].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_channel_noise_statistics() {
+        let input = vec![0.0; 100_000];
+        let out = Channel::new().with_noise(0.5).apply(&input, 1.0);
+        let mean = out.iter().sum::<f64>() / out.len() as f64;
+        let rms = (out.iter().map(|x| x * x).sum::<f64>() / out.len() as f64).sqrt();
+        assert!(mean.abs() < 0.01);
+        assert!((rms - 0.5).abs() < 0.01);
+
+        let again = Channel::new().with_noise(0.5).apply(&input, 1.0);
+        assert_eq!(out, again);
+    }
+}
@@ -0,0 +1,916 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+use anyhow::{Context, anyhow, bail};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use lcv::{
+    analysis::{
+        ber::{self, BerPoint},
+        sampler::{Sampler, Shaping},
+    },
+    coder::{
+        BitCell, LineCoder, SigElement,
+        catalog::{self, BlockName, Settings},
+        machine::Definition,
+        registry::{Entry, Registry},
+    },
+    export::{
+        svg::Svg,
+        vcd::{Timescale, Vcd},
+        wav::Wav,
+    },
+    generator::Pattern,
+    util::{BitOrder, InputFormat},
+};
+
+const MAX_SNR_POINTS: usize = 1000;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Coder definition file to add to the playground's methods, may be repeated
+    #[arg(long = "coder-file", value_name = "FILE")]
+    pub coder_files: Vec<PathBuf>,
+}
+
+impl Cli {
+    pub fn registry(&self) -> anyhow::Result<Registry> {
+        let mut registry = Registry::builtin();
+        for path in &self.coder_files {
+            let entry = load_coder(path)?;
+            if let Some(taken) = registry.get(entry.id()) {
+                bail!(
+                    "`{}`: coder id `{}` is already taken by {}",
+                    path.display(),
+                    entry.id(),
+                    taken.name()
+                );
+            }
+            registry.register(entry);
+        }
+        Ok(registry)
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Encode a bit sequence and print its signal elements
+    Encode(EncodeArgs),
+    /// Simulate the bit error rate over an AWGN channel across an SNR sweep
+    Ber(BerArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CoderArgs {
+    #[arg(
+        short,
+        long,
+        required_unless_present = "coder_file",
+        help = method_help(),
+        long_help = method_long_help()
+    )]
+    method: Option<String>,
+    /// Coder definition file to use instead of a built-in method
+    #[arg(long, value_name = "FILE", conflicts_with = "method")]
+    coder_file: Option<PathBuf>,
+    /// Block code applied before the line code (none, 4b5b, 8b10b)
+    #[arg(long, value_parser = parse_block, default_value = "none")]
+    block: BlockName,
+    /// Bit rate in bits per second
+    #[arg(long, default_value_t = 1.0)]
+    bit_rate: f64,
+    /// Signal amplitude in volts
+    #[arg(long, default_value_t = 1.0)]
+    amplitude: f64,
+    /// Index of the initial state, as listed in the TUI settings panel
+    #[arg(long)]
+    initial_state: Option<usize>,
+    /// Duty cycle for RZ and Manchester schemes, between 0 and 1
+    #[arg(long, default_value_t = 0.5)]
+    duty: f64,
+}
+
+impl CoderArgs {
+    fn entry(&self, registry: &Registry) -> anyhow::Result<Entry> {
+        match (&self.method, &self.coder_file) {
+            (Some(id), _) => registry.get(id).cloned().ok_or_else(|| {
+                anyhow!("unknown method `{id}`, expected one of {}", registry.ids())
+            }),
+            (None, Some(path)) => load_coder(path),
+            (None, None) => bail!("a method or a coder file is required"),
+        }
+    }
+
+    fn build(&self, entry: &Entry) -> anyhow::Result<Box<dyn LineCoder>> {
+        let schema = entry.schema();
+        let init_states = schema.init_states().len();
+        let init_state = self
+            .initial_state
+            .unwrap_or_else(|| schema.def_init_state());
+        if init_states > 0 && init_state >= init_states {
+            bail!("initial state must be below {init_states} for this method");
+        }
+        if !self.bit_rate.is_finite() || self.bit_rate <= 0.0 {
+            bail!("bit rate must be positive and finite");
+        }
+        if !self.amplitude.is_finite() {
+            bail!("amplitude must be finite");
+        }
+        if !(self.duty > 0.0 && self.duty < 1.0) {
+            bail!("duty cycle must be between 0 and 1");
+        }
+
+        let settings = Settings {
+            bit_rate: self.bit_rate,
+            amplitude: self.amplitude,
+            init_state,
+            duty: self.duty,
+        };
+        Ok(catalog::build(entry, self.block, &settings))
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct EncodeArgs {
+    #[command(flatten)]
+    coder: CoderArgs,
+    /// Bits to encode, written in the `--from` format
+    #[arg(short, long, conflicts_with = "input")]
+    bits: Option<String>,
+    /// File to read the bits from, `-` for stdin (default when no bits are given)
+    #[arg(short, long, value_name = "FILE")]
+    input: Option<PathBuf>,
+    /// Input format (bin, hex, text, bytes)
+    #[arg(long, value_parser = parse_input_format, default_value = "bin")]
+    from: InputFormat,
+    /// Send the bits of each text or byte character least significant first
+    #[arg(long)]
+    lsb_first: bool,
+    /// Generate the bits instead of reading them (prbs7, prbs15, prbs23, prbs31, zeros, ones, alt, 1in8, 3in24)
+    #[arg(long, value_parser = parse_pattern, conflicts_with_all = ["bits", "input"])]
+    pattern: Option<Pattern>,
+    /// Number of generated bits
+    #[arg(long, default_value_t = 127, requires = "pattern")]
+    length: usize,
+    /// Initial PRBS register state (all ones by default)
+    #[arg(long, requires = "pattern")]
+    seed: Option<u32>,
+    /// File to write to instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    #[arg(short, long, value_enum, default_value_t = EncodeFormat::Table)]
+    format: EncodeFormat,
+    /// VCD timescale such as `1ns` or `10 us` (default derived from the bit time)
+    #[arg(long, value_parser = str::parse::<Timescale>)]
+    timescale: Option<Timescale>,
+    /// Add a bit clock track to the VCD output
+    #[arg(long)]
+    clock: bool,
+    /// Add a track with the line-coded bits to the VCD output
+    #[arg(long)]
+    data: bool,
+    /// WAV sample rate in hertz
+    #[arg(long, default_value_t = Wav::DEFAULT_SAMPLE_RATE)]
+    sample_rate: u32,
+    /// Bit rate the WAV audio is played at (default the coder's bit rate)
+    #[arg(long)]
+    audio_bit_rate: Option<f64>,
+    /// Pulse shaping of the WAV audio (none, ramp:<bits>, rc:<roll-off>)
+    #[arg(long, value_parser = str::parse::<Shaping>, default_value = "none")]
+    shaping: Shaping,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct BerArgs {
+    #[command(flatten)]
+    coder: CoderArgs,
+    /// Number of random bits simulated at each SNR
+    #[arg(short = 'n', long, default_value_t = 100_000)]
+    bits: usize,
+    /// First SNR of the sweep, in dB
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    snr_min: f64,
+    /// Last SNR of the sweep, in dB
+    #[arg(long, default_value_t = 14.0, allow_negative_numbers = true)]
+    snr_max: f64,
+    /// SNR increment between points, in dB
+    #[arg(long, default_value_t = 1.0)]
+    snr_step: f64,
+    /// Pulse shaping of the transmitted waveform (none, ramp:<bits>, rc:<roll-off>)
+    #[arg(long, value_parser = str::parse::<Shaping>, default_value = "none")]
+    shaping: Shaping,
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EncodeFormat {
+    Table,
+    Csv,
+    Json,
+    /// Value Change Dump, readable by GTKWave and HDL simulators
+    Vcd,
+    /// Plot of the waveform with bit boundaries and labels
+    Svg,
+    /// 16-bit mono PCM audio of the waveform
+    Wav,
+}
+
+fn load_coder(path: &PathBuf) -> anyhow::Result<Entry> {
+    let src =
+        fs::read_to_string(path).with_context(|| format!("failed to read `{}`", path.display()))?;
+    let def: Definition = src
+        .parse()
+        .map_err(|err| anyhow!("`{}`: {err}", path.display()))?;
+    Ok(def.entry())
+}
+
+fn method_help() -> String {
+    format!(
+        "Line coding method ({}, or the id of a --coder-file)",
+        Registry::builtin().ids()
+    )
+}
+
+fn method_long_help() -> String {
+    let mut help = Registry::builtin().entries().iter().fold(
+        String::from("Line coding method, one of:"),
+        |mut help, entry| {
+            let _ = write!(help, "\n  {:<16} {}", entry.id(), entry.description());
+            help
+        },
+    );
+    help.push_str("\nor the id of a coder loaded with the global --coder-file option");
+    help
+}
+
+fn parse_input_format(id: &str) -> Result<InputFormat, String> {
+    InputFormat::from_id(id).ok_or_else(|| format!("unknown input format `{id}`"))
+}
+
+fn parse_pattern(id: &str) -> Result<Pattern, String> {
+    Pattern::from_id(id).ok_or_else(|| format!("unknown pattern `{id}`"))
+}
+
+fn parse_block(id: &str) -> Result<BlockName, String> {
+    BlockName::from_id(id).ok_or_else(|| format!("unknown block code `{id}`"))
+}
+
+pub fn run(command: Command, registry: &Registry) -> anyhow::Result<()> {
+    let (out, path) = match command {
+        Command::Encode(args) => {
+            let path = args.output.clone();
+            (encode(args, registry)?, path)
+        }
+        Command::Ber(args) => (simulate_ber(args, registry)?.into_bytes(), None),
+    };
+
+    match path {
+        Some(path) => fs::write(&path, out)
+            .with_context(|| format!("failed to write `{}`", path.display()))?,
+        None => io::stdout().lock().write_all(&out)?,
+    }
+    Ok(())
+}
+
+fn read_bits(args: &EncodeArgs) -> anyhow::Result<Box<[u8]>> {
+    let src = match (&args.bits, &args.input) {
+        (Some(bits), _) => bits.as_bytes().to_vec(),
+        (None, Some(path)) if path.as_os_str() != "-" => {
+            fs::read(path).with_context(|| format!("failed to read `{}`", path.display()))?
+        }
+        _ => {
+            let mut buf = Vec::new();
+            io::stdin().read_to_end(&mut buf)?;
+            buf
+        }
+    };
+    let src = match args.from {
+        InputFormat::Text => src
+            .strip_suffix(b"\r\n")
+            .or_else(|| src.strip_suffix(b"\n"))
+            .unwrap_or(&src),
+        _ => &src,
+    };
+    let order = if args.lsb_first {
+        BitOrder::LsbFirst
+    } else {
+        BitOrder::MsbFirst
+    };
+    args.from.to_bits(src, order).ok_or_else(|| {
+        let msg = match args.from {
+            InputFormat::Binary => "invalid bit sequence, expected only 0s and 1s",
+            InputFormat::Hex => "invalid hex sequence, expected only hex digits",
+            InputFormat::Text if !src.is_empty() => "input is not valid UTF-8 text",
+            _ => "empty input",
+        };
+        anyhow!(msg)
+    })
+}
+
+fn encode(args: EncodeArgs, registry: &Registry) -> anyhow::Result<Vec<u8>> {
+    let bits = match args.pattern {
+        Some(_) if args.length == 0 => bail!("pattern length must be positive"),
+        Some(pattern) => pattern.generate(args.length, args.seed.unwrap_or(u32::MAX)),
+        None => read_bits(&args)?,
+    };
+
+    let entry = args.coder.entry(registry)?;
+    let coder = args.coder.build(&entry)?;
+    let sigs = coder.encode(&bits);
+    // A block code changes the bits the line coder sends.
+    let coded = || {
+        coder
+            .cells(&bits)
+            .iter()
+            .map(BitCell::bit)
+            .collect::<Box<[_]>>()
+    };
+    let format = match args.format {
+        EncodeFormat::Table => Format::Table,
+        EncodeFormat::Csv => Format::Csv,
+        EncodeFormat::Json => Format::Json,
+        EncodeFormat::Vcd => {
+            let mut vcd = Vcd::new(coder.bit_time()).with_clock(args.clock);
+            if let Some(timescale) = args.timescale {
+                vcd = vcd.with_timescale(timescale);
+            }
+            return Ok(vcd
+                .render(&sigs, args.data.then(coded).as_deref())
+                .into_bytes());
+        }
+        EncodeFormat::Svg => {
+            return Ok(Svg::new(coder.bit_time())
+                .with_title(catalog::label(&entry, args.coder.block))
+                .render(&sigs, Some(&coded()))
+                .into_bytes());
+        }
+        EncodeFormat::Wav => {
+            if args.sample_rate == 0 || args.sample_rate > Wav::MAX_SAMPLE_RATE {
+                bail!(
+                    "sample rate must be between 1 and {} Hz",
+                    Wav::MAX_SAMPLE_RATE
+                );
+            }
+            let mut wav = Wav::new(coder.bit_time())
+                .with_sample_rate(args.sample_rate)
+                .with_shaping(args.shaping);
+            if let Some(rate) = args.audio_bit_rate {
+                if !rate.is_finite() || rate <= 0.0 {
+                    bail!("audio bit rate must be positive");
+                }
+                wav = wav.with_bit_rate(rate);
+            }
+            if wav.samples_per_bit() < 2.0 {
+                bail!("bit rate must be at most half the sample rate");
+            }
+            if wav.file_len(&sigs) > u32::MAX as u64 {
+                bail!("WAV output would exceed 4 GiB, raise the audio bit rate or send fewer bits");
+            }
+            return wav.render(&sigs).map_err(|err| anyhow!(err));
+        }
+    };
+
+    Ok(format_sigs(&sigs, format).into_bytes())
+}
+
+fn simulate_ber(args: BerArgs, registry: &Registry) -> anyhow::Result<String> {
+    if args.bits == 0 {
+        bail!("at least one bit must be simulated");
+    }
+    if args.snr_step.is_nan() || args.snr_step <= 0.0 {
+        bail!("SNR step must be positive");
+    }
+    if !args.snr_min.is_finite() || !args.snr_max.is_finite() {
+        bail!("SNR bounds must be finite");
+    }
+    if args.snr_max < args.snr_min {
+        bail!("SNR range is empty");
+    }
+
+    let n = ((args.snr_max - args.snr_min) / args.snr_step + 1e-9).floor();
+    if n >= MAX_SNR_POINTS as f64 {
+        bail!("the sweep would exceed {MAX_SNR_POINTS} SNR points, raise the SNR step");
+    }
+    let n = n as usize;
+    let snrs = (0..=n).map(|i| args.snr_min + i as f64 * args.snr_step);
+    let sampler = Sampler::new()
+        .with_bit_rate(args.coder.bit_rate)
+        .with_samples_per_bit(ber::SAMPLES_PER_BIT)
+        .with_shaping(args.shaping);
+    let points = ber::sweep(
+        args.coder.build(&args.coder.entry(registry)?)?.as_ref(),
+        &sampler,
+        args.bits,
+        snrs,
+    );
+    Ok(format_ber(&points, args.format))
+}
+
+fn format_sigs(sigs: &[SigElement], format: Format) -> String {
+    let mut out = String::new();
+    match format {
+        Format::Table => {
+            let _ = writeln!(out, "{:>12} {:>12} {:>8}", "ti", "tf", "lvl");
+            for sig in sigs {
+                let _ = writeln!(out, "{:>12} {:>12} {:>8}", sig.ti(), sig.tf(), sig.lvl());
+            }
+        }
+        Format::Csv => {
+            out.push_str("ti,tf,lvl\n");
+            for sig in sigs {
+                let _ = writeln!(out, "{},{},{}", sig.ti(), sig.tf(), sig.lvl());
+            }
+        }
+        Format::Json => {
+            out.push('[');
+            for (i, sig) in sigs.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(
+                    out,
+                    r#"{{"ti":{},"tf":{},"lvl":{}}}"#,
+                    sig.ti(),
+                    sig.tf(),
+                    sig.lvl()
+                );
+            }
+            out.push_str("]\n");
+        }
+    }
+
+    out
+}
+
+fn format_ber(points: &[BerPoint], format: Format) -> String {
+    let mut out = String::new();
+    match format {
+        Format::Table => {
+            let _ = writeln!(
+                out,
+                "{:>8} {:>10} {:>10} {:>12}",
+                "snr_db", "bits", "errors", "ber"
+            );
+            for p in points {
+                let _ = writeln!(
+                    out,
+                    "{:>8} {:>10} {:>10} {:>12.3e}",
+                    p.snr_db(),
+                    p.bits(),
+                    p.errors(),
+                    p.ber()
+                );
+            }
+        }
+        Format::Csv => {
+            out.push_str("snr_db,bits,errors,ber\n");
+            for p in points {
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{}",
+                    p.snr_db(),
+                    p.bits(),
+                    p.errors(),
+                    p.ber()
+                );
+            }
+        }
+        Format::Json => {
+            out.push('[');
+            for (i, p) in points.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(
+                    out,
+                    r#"{{"snr_db":{},"bits":{},"errors":{},"ber":{}}}"#,
+                    p.snr_db(),
+                    p.bits(),
+                    p.errors(),
+                    p.ber()
+                );
+            }
+            out.push_str("]\n");
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Cli, Command, Format, format_sigs};
+    use lcv::{SigElement, analysis::sampler::Shaping, coder::registry::Registry};
+
+    fn encode(args: super::EncodeArgs) -> anyhow::Result<Vec<u8>> {
+        super::encode(args, &Registry::builtin())
+    }
+
+    fn encode_text(args: super::EncodeArgs) -> anyhow::Result<String> {
+        Ok(String::from_utf8(encode(args)?)?)
+    }
+
+    #[test]
+    fn test_cli_formats() {
+        let sigs = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, -0.5),
+        ];
+        assert_eq!(
+            format_sigs(&sigs, Format::Csv),
+            "ti,tf,lvl\n0,1,1\n1,2,-0.5\n"
+        );
+        assert_eq!(
+            format_sigs(&sigs, Format::Json),
+            "[{\"ti\":0,\"tf\":1,\"lvl\":1},{\"ti\":1,\"tf\":2,\"lvl\":-0.5}]\n"
+        );
+        assert_eq!(format_sigs(&[], Format::Json), "[]\n");
+    }
+
+    #[test]
+    fn test_cli_rejects_unknown_method() {
+        assert!(Cli::try_parse_from(["lcv", "encode", "-m", "hdb3", "-b", "10"]).is_ok());
+        let cli = Cli::try_parse_from(["lcv", "encode", "-m", "hdb9", "-b", "10"]);
+        let Some(Command::Encode(args)) = cli.unwrap().command else {
+            panic!("expected the encode command");
+        };
+        let err = encode(args).unwrap_err().to_string();
+        assert!(err.starts_with("unknown method `hdb9`"), "{err}");
+        assert!(Cli::try_parse_from(["lcv"]).unwrap().command.is_none());
+    }
+
+    #[test]
+    fn test_cli_encode_vcd() {
+        let cli = Cli::try_parse_from([
+            "lcv",
+            "encode",
+            "-m",
+            "ami",
+            "-b",
+            "101",
+            "-f",
+            "vcd",
+            "--timescale",
+            "10us",
+            "--data",
+        ])
+        .unwrap();
+        let Some(Command::Encode(args)) = cli.command else {
+            panic!("expected the encode command");
+        };
+
+        let out = encode_text(args).unwrap();
+        assert!(out.contains("$timescale 10 us $end"));
+        assert!(out.contains("$var wire 1 # data $end"));
+        assert!(!out.contains("clock"));
+        assert!(out.ends_with("#300000\n"));
+    }
+
+    #[test]
+    fn test_cli_encode_vcd_tracks_coded_bits() {
+        let cli = Cli::try_parse_from([
+            "lcv",
+            "encode",
+            "-m",
+            "nrzl",
+            "--block",
+            "4b5b",
+            "-b",
+            "0000",
+            "-f",
+            "vcd",
+            "--timescale",
+            "1s",
+            "--data",
+        ])
+        .unwrap();
+        let Some(Command::Encode(args)) = cli.command else {
+            panic!("expected the encode command");
+        };
+
+        let out = encode_text(args).unwrap();
+        assert!(out.ends_with("#0\nr1 !\n1#\n#4\nr-1 !\n0#\n#5\n"), "{out}");
+    }
+
+    #[test]
+    fn test_cli_encode_svg() {
+        let cli = Cli::try_parse_from([
+            "lcv", "encode", "-m", "mlt3", "--block", "4b5b", "-b", "0110", "-f", "svg",
+        ])
+        .unwrap();
+        let Some(Command::Encode(args)) = cli.command else {
+            panic!("expected the encode command");
+        };
+
+        let out = encode_text(args).unwrap();
+        assert!(out.starts_with("<svg "));
+        assert!(out.contains("4B/5B + MLT-3"));
+    }
+
+    #[test]
+    fn test_cli_encode_input_formats() {
+        let encode = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                ["lcv", "encode", "-m", "nrzl", "-f", "csv"]
+                    .iter()
+                    .chain(args),
+            )
+            .unwrap();
+            let Some(Command::Encode(args)) = cli.command else {
+                panic!("expected the encode command");
+            };
+            encode_text(args)
+        };
+        let rows = |out: String| out.lines().count() - 1;
+
+        assert_eq!(rows(encode(&["--from", "hex", "-b", "0xA5"]).unwrap()), 8);
+        assert_eq!(rows(encode(&["--from", "text", "-b", "Hi"]).unwrap()), 16);
+        assert_eq!(rows(encode(&["--from", "text", "-b", "Hi\n"]).unwrap()), 16);
+        assert_eq!(
+            rows(encode(&["--from", "text", "-b", "Hi\r\n"]).unwrap()),
+            16
+        );
+        assert_eq!(rows(encode(&["--from", "text", "-b", "Hi\r"]).unwrap()), 24);
+        assert_eq!(
+            encode(&["--from", "text", "--lsb-first", "-b", "A"]).unwrap(),
+            encode(&["-b", "10000010"]).unwrap()
+        );
+        assert!(encode(&["--from", "hex", "-b", "xyz"]).is_err());
+        assert!(encode(&["--from", "text", "-b", ""]).is_err());
+        assert!(Cli::try_parse_from(["lcv", "encode", "-m", "ami", "--from", "oct"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_encode_pattern() {
+        let encode = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                ["lcv", "encode", "-m", "nrzl", "-f", "csv"]
+                    .iter()
+                    .chain(args),
+            )?;
+            let Some(Command::Encode(args)) = cli.command else {
+                panic!("expected the encode command");
+            };
+            encode_text(args)
+        };
+        let rows = |out: String| out.lines().count() - 1;
+
+        assert_eq!(rows(encode(&["--pattern", "prbs7"]).unwrap()), 127);
+        assert_eq!(
+            rows(encode(&["--pattern", "3in24", "--length", "48"]).unwrap()),
+            48
+        );
+        assert_ne!(
+            encode(&["--pattern", "prbs15", "--seed", "1"]).unwrap(),
+            encode(&["--pattern", "prbs15"]).unwrap()
+        );
+        assert!(encode(&["--pattern", "prbs9"]).is_err());
+        assert!(encode(&["--pattern", "ones", "--length", "0"]).is_err());
+        assert!(encode(&["--pattern", "ones", "-b", "101"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_encode_coder_file() {
+        let path = std::env::temp_dir().join(format!("lcv-test-{}.lcc", std::process::id()));
+        std::fs::write(&path, "id half\nstate s\n0 -> s : -1\n1 -> s : 1/0.5 0\n").unwrap();
+        let file = path.to_str().unwrap();
+
+        let cli = Cli::try_parse_from([
+            "lcv",
+            "encode",
+            "--coder-file",
+            file,
+            "-b",
+            "10",
+            "-f",
+            "csv",
+        ]);
+        let Some(Command::Encode(args)) = cli.unwrap().command else {
+            panic!("expected the encode command");
+        };
+        assert_eq!(
+            encode_text(args).unwrap(),
+            "ti,tf,lvl\n0,0.5,1\n0.5,1,0\n1,2,-1\n"
+        );
+        assert!(Cli::try_parse_from(["lcv", "encode", "-b", "10"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "lcv",
+                "encode",
+                "-m",
+                "ami",
+                "--coder-file",
+                file,
+                "-b",
+                "1"
+            ])
+            .is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "lcv",
+            "--coder-file",
+            file,
+            "encode",
+            "-m",
+            "HALF",
+            "-b",
+            "10",
+            "-f",
+            "csv",
+        ])
+        .unwrap();
+        let registry = cli.registry().unwrap();
+        let Some(Command::Encode(args)) = cli.command else {
+            panic!("expected the encode command");
+        };
+        assert_eq!(
+            String::from_utf8(super::encode(args, &registry).unwrap()).unwrap(),
+            "ti,tf,lvl\n0,0.5,1\n0.5,1,0\n1,2,-1\n"
+        );
+
+        let cli = Cli::try_parse_from(["lcv", "--coder-file", file, "--coder-file", file]);
+        let err = cli.unwrap().registry().unwrap_err().to_string();
+        assert!(
+            err.ends_with("coder id `half` is already taken by half"),
+            "{err}"
+        );
+        std::fs::write(&path, "id NRZL\nstate s\n0 -> s : 0\n1 -> s : 1\n").unwrap();
+        let cli = Cli::try_parse_from(["lcv", "--coder-file", file]);
+        let err = cli.unwrap().registry().unwrap_err().to_string();
+        assert!(
+            err.ends_with("coder id `NRZL` is already taken by NRZ-L"),
+            "{err}"
+        );
+
+        std::fs::write(&path, "id broken\nstate s\n0 -> t : 0\n1 -> s : 1\n").unwrap();
+        let cli = Cli::try_parse_from(["lcv", "encode", "--coder-file", file, "-b", "1"]);
+        let Some(Command::Encode(args)) = cli.unwrap().command else {
+            panic!("expected the encode command");
+        };
+        let err = encode_text(args).unwrap_err().to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.ends_with("line 3: unknown state `t`"), "{err}");
+    }
+
+    #[test]
+    fn test_cli_ber_sweep() {
+        let cli = Cli::try_parse_from([
+            "lcv",
+            "ber",
+            "-m",
+            "ami",
+            "-n",
+            "800",
+            "--snr-min",
+            "-2",
+            "--snr-max",
+            "2",
+            "--snr-step",
+            "2",
+            "-f",
+            "csv",
+        ])
+        .unwrap();
+        let Some(Command::Ber(args)) = cli.command else {
+            panic!("expected the ber command");
+        };
+
+        let out = super::simulate_ber(args, &Registry::builtin()).unwrap();
+        let rows = out.lines().collect::<Box<[_]>>();
+        assert_eq!(rows[0], "snr_db,bits,errors,ber");
+        assert_eq!(rows.len(), 4);
+        assert!(rows[1].starts_with("-2,800,"));
+        assert!(rows[3].starts_with("2,800,"));
+    }
+
+    #[test]
+    fn test_cli_ber_bounds_the_sweep() {
+        let sweep = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                ["lcv", "ber", "-m", "nrzl", "-n", "1", "-f", "csv"]
+                    .iter()
+                    .chain(args),
+            )
+            .unwrap();
+            let Some(Command::Ber(args)) = cli.command else {
+                panic!("expected the ber command");
+            };
+            super::simulate_ber(args, &Registry::builtin())
+        };
+
+        for args in [
+            &["--snr-max", "inf"][..],
+            &["--snr-min=-inf"],
+            &["--snr-max", "NaN"],
+            &["--snr-step", "1e-300"],
+            &["--snr-max", "1000"],
+        ] {
+            assert!(sweep(args).is_err(), "{args:?}");
+        }
+        let out = sweep(&["--snr-max", "999"]).unwrap();
+        assert_eq!(out.lines().count(), 1001);
+    }
+
+    #[test]
+    fn test_cli_ber_shaping() {
+        let parse = |shaping| {
+            Cli::try_parse_from([
+                "lcv",
+                "ber",
+                "-m",
+                "nrzl",
+                "-n",
+                "200",
+                "-f",
+                "csv",
+                "--shaping",
+                shaping,
+            ])
+        };
+        assert!(parse("rc:2").is_err());
+
+        let Some(Command::Ber(args)) = parse("rc:0.5").unwrap().command else {
+            panic!("expected the ber command");
+        };
+        assert_eq!(args.shaping, Shaping::RaisedCosine(0.5));
+        let out = super::simulate_ber(args, &Registry::builtin()).unwrap();
+        assert_eq!(out.lines().last(), Some("14,200,0,0"));
+    }
+
+    #[test]
+    fn test_cli_encode_wav() {
+        let encode = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                ["lcv", "encode", "-m", "manchester", "-b", "10", "-f", "wav"]
+                    .iter()
+                    .chain(args),
+            )?;
+            let Some(Command::Encode(args)) = cli.command else {
+                panic!("expected the encode command");
+            };
+            encode(args)
+        };
+
+        let wav = encode(&["--sample-rate", "8000", "--audio-bit-rate", "1000"]).unwrap();
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(wav.len(), 44 + 2 * 16);
+        assert!(encode(&["--sample-rate", "8000", "--audio-bit-rate", "5000"]).is_err());
+        assert!(encode(&["--bit-rate", "48000"]).is_err());
+        assert!(encode(&["--sample-rate", "0"]).is_err());
+        let err = encode(&["--sample-rate", "3000000000", "--audio-bit-rate", "1e9"])
+            .map(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().starts_with("sample rate must be"), "{err}");
+        let err = encode(&["--audio-bit-rate", "1e-5"])
+            .map(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().contains("4 GiB"), "{err}");
+        assert_eq!(
+            encode(&["--bit-rate", "1000", "--shaping", "rc:0.5"])
+                .unwrap()
+                .len(),
+            44 + 2 * 96
+        );
+        assert_eq!(encode(&["--bit-rate", "1000"]).unwrap().len(), 44 + 2 * 96);
+    }
+
+    #[test]
+    fn test_cli_rejects_invalid_coder_settings() {
+        for arg in [
+            "--bit-rate=0",
+            "--bit-rate=-1",
+            "--bit-rate=inf",
+            "--bit-rate=NaN",
+            "--amplitude=NaN",
+            "--amplitude=inf",
+            "--duty=0",
+            "--duty=1",
+            "--duty=3",
+            "--duty=NaN",
+        ] {
+            let cli = Cli::try_parse_from(["lcv", "encode", "-m", "nrzl", "-b", "10", arg]);
+            let Some(Command::Encode(args)) = cli.unwrap().command else {
+                panic!("expected the encode command");
+            };
+            assert!(encode(args).is_err(), "{arg}");
+        }
+    }
+}
@@ -1,14 +1,39 @@
 pub mod ami;
+pub mod b8zs;
+pub mod block;
+pub mod catalog;
 pub mod hdb3;
+pub mod machine;
 pub mod manch;
 pub mod mlt3;
 pub mod nrz;
+pub mod registry;
 pub mod rz;
+pub mod stream;
 
-use std::any::{Any, TypeId};
+use std::any::Any;
 
 const GLOB_BASE_TB: f64 = 1.0;
 const GLOB_BASE_V: f64 = 1.0;
+const GLOB_BASE_DUTY: f64 = 0.5;
+const MIN_DUTY: f64 = 0.05;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    #[default]
+    Positive,
+    Negative,
+}
+
+impl Polarity {
+    #[inline(always)]
+    pub fn sign(&self) -> f64 {
+        match self {
+            Self::Positive => 1.0,
+            Self::Negative => -1.0,
+        }
+    }
+}
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct SigElement {
@@ -38,8 +63,89 @@ impl SigElement {
     }
 }
 
-pub trait LineCoder: 'static {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]>;
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    #[default]
+    Data,
+    Violation,
+    Balancing,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BitCell {
+    ti: f64,
+    tf: f64,
+    bit: u8,
+    mark: Mark,
+}
+
+impl BitCell {
+    pub fn new(ti: f64, tf: f64, bit: u8, mark: Mark) -> Self {
+        Self { ti, tf, bit, mark }
+    }
+
+    #[inline(always)]
+    pub fn ti(&self) -> f64 {
+        self.ti
+    }
+
+    #[inline(always)]
+    pub fn tf(&self) -> f64 {
+        self.tf
+    }
+
+    #[inline(always)]
+    pub fn bit(&self) -> u8 {
+        self.bit
+    }
+
+    #[inline(always)]
+    pub fn mark(&self) -> Mark {
+        self.mark
+    }
+}
+
+pub trait LineDecoder {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]>;
+}
+
+// `push` may hold back the last few bits while a substitution or a block is
+// still undecided, and `finish` emits them once the input has ended.
+pub trait StreamEncoder: 'static {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<SigElement>);
+
+    fn finish(&mut self, _out: &mut Vec<SigElement>) {}
+
+    fn boxed_clone(&self) -> Box<dyn StreamEncoder>;
+}
+
+impl Clone for Box<dyn StreamEncoder> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.boxed_clone()
+    }
+}
+
+pub trait LineCoder: LineDecoder + Any {
+    fn stream(&self) -> Box<dyn StreamEncoder>;
+
+    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
+        let mut enc = self.stream();
+        let mut out = Vec::new();
+        enc.push(bits, &mut out);
+        enc.finish(&mut out);
+        out.into_boxed_slice()
+    }
+
+    fn bit_time(&self) -> f64;
+
+    fn cells(&self, bits: &[u8]) -> Box<[BitCell]> {
+        let tb = self.bit_time();
+        bits.iter()
+            .enumerate()
+            .map(|(i, &bit)| BitCell::new(i as f64 * tb, (i + 1) as f64 * tb, bit, Mark::Data))
+            .collect()
+    }
 
     fn boxed(self) -> Box<dyn LineCoder + 'static>
     where
@@ -47,30 +153,106 @@ pub trait LineCoder: 'static {
     {
         Box::new(self)
     }
+}
 
-    unsafe fn downcast_raw_ref(&self, id: TypeId) -> Option<*const ()> {
-        if TypeId::of::<Self>() == id {
-            Some(self as *const Self as *const ())
-        } else {
-            None
-        }
+// Rates that are not finite and positive leave the bit time unchanged.
+#[inline]
+fn bit_time_for(rate: f64, tb: f64) -> f64 {
+    if rate.is_finite() && rate > 0.0 {
+        rate.recip()
+    } else {
+        tb
     }
 }
 
-#[allow(unused)]
+// Amplitudes set the magnitude only, keeping the sign that encodes the
+// initial polarity; non-finite ones are ignored.
+#[inline]
+fn level_for(v: f64, cur: f64) -> f64 {
+    if v.is_finite() {
+        v.abs().copysign(cur)
+    } else {
+        cur
+    }
+}
+
+// Duty cycles are kept clear of 0 and 1; non-finite ones are ignored.
+#[inline]
+fn duty_for(duty: f64, cur: f64) -> f64 {
+    if duty.is_finite() {
+        duty.clamp(MIN_DUTY, 1.0 - MIN_DUTY)
+    } else {
+        cur
+    }
+}
+
+pub fn level_at(sigs: &[SigElement], t: f64) -> f64 {
+    sigs.get(sigs.partition_point(|sig| sig.tf <= t))
+        .map(SigElement::lvl)
+        .unwrap_or_default()
+}
+
+fn probe(sigs: &[SigElement], tb: f64, at: f64) -> impl Iterator<Item = f64> + '_ {
+    let n = sigs.last().map(|sig| (sig.tf / tb).round() as usize);
+    (0..n.unwrap_or_default()).map(move |i| level_at(sigs, (i as f64 + at) * tb))
+}
+
 impl dyn LineCoder {
     #[inline]
     pub fn is<T: Any>(&self) -> bool {
-        self.downcast_ref::<T>().is_some()
+        (self as &dyn Any).is::<T>()
     }
 
+    #[inline]
     pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
-        unsafe {
-            let ptr = self.downcast_raw_ref(TypeId::of::<T>())?;
-            if ptr.is_null() {
-                None
-            } else {
-                Some(&*(ptr as *const T))
+        (self as &dyn Any).downcast_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineCoder, ami::Ami, catalog::Settings, nrz::Nrzl, registry::Registry};
+
+    #[test]
+    fn test_downcast_boxed_coder() {
+        let coder = Ami::new().with_bit_rate(2.0).boxed();
+        assert!(coder.is::<Ami>());
+        assert!(!coder.is::<Nrzl>());
+        assert_eq!(coder.downcast_ref::<Ami>().unwrap().bit_time(), 0.5);
+        assert!(coder.downcast_ref::<Nrzl>().is_none());
+    }
+
+    #[test]
+    fn test_invalid_rates_and_amplitudes_are_ignored() {
+        for (rate, duty) in [
+            (0.0, f64::NAN),
+            (-2.0, f64::INFINITY),
+            (f64::NAN, f64::NEG_INFINITY),
+            (f64::INFINITY, f64::NAN),
+        ] {
+            let settings = Settings {
+                bit_rate: rate,
+                amplitude: f64::NAN,
+                duty,
+                ..Default::default()
+            };
+            for entry in Registry::builtin().entries() {
+                let coder = entry.build(&settings);
+                assert_eq!(coder.bit_time(), 1.0, "{} at {rate}", entry.id());
+                let sigs = coder.encode(&[1, 0, 1]);
+                assert!(
+                    sigs.iter().all(|sig| [sig.ti(), sig.tf(), sig.lvl()]
+                        .iter()
+                        .all(|x| x.is_finite())),
+                    "{} at {rate} with duty {duty}",
+                    entry.id()
+                );
+                assert_eq!(
+                    coder.decode(&sigs).as_ref(),
+                    &[1, 0, 1],
+                    "{} with duty {duty}",
+                    entry.id()
+                );
             }
         }
     }
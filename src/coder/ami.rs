@@ -1,4 +1,7 @@
-use super::{LineCoder, SigElement};
+use super::{
+    LineCoder, LineDecoder, Polarity, SigElement, StreamEncoder,
+    stream::{Step, Stepper},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Ami {
@@ -20,38 +23,86 @@ impl Ami {
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_polarity(mut self, pol: Polarity) -> Self {
+        self.v = self.v.abs() * pol.sign();
+        self
+    }
 }
 
 impl LineCoder for Ami {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let mut t = 0.0;
-        let mut lvl = self.v;
-
-        bits.iter()
-            .filter_map(|&bit| {
-                let mut res = None;
-                let tf = t + self.tb;
-                if bit == 1 {
-                    lvl = -lvl;
-                    if tf > t {
-                        res = Some(SigElement::new(t, tf, lvl));
-                    }
-                } else if tf > t {
-                    res = Some(SigElement::new(t, tf, 0.0));
-                }
-
-                t += self.tb;
-                res
-            })
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+// The amplitude's sign is the polarity of the last mark.
+impl Step for Ami {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        let tf = t + self.tb;
+        if bit == 1 {
+            self.v = -self.v;
+            if tf > t {
+                out.push(SigElement::new(t, tf, self.v));
+            }
+        } else if tf > t {
+            out.push(SigElement::new(t, tf, 0.0));
+        }
+    }
+}
+
+impl LineDecoder for Ami {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let thr = self.v.abs() / 2.0;
+        super::probe(sigs, self.tb, 0.5)
+            .map(|lvl| (lvl.abs() > thr) as u8)
             .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::coder::{LineCoder, SigElement, ami::Ami};
+    use crate::coder::{LineCoder, Polarity, SigElement, ami::Ami};
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    test_round_trip!(test_ami_round_trip: Ami::new());
+
+    test_round_trip!(test_ami_configured_round_trip: Ami::new()
+        .with_amplitude(2.5)
+        .with_initial_polarity(Polarity::Positive));
+
+    test_stream!(test_ami_stream: Ami::new());
+
+    test_len_case!(test_ami_configured_cases: Ami::new()
+        .with_amplitude(2.0)
+        .with_initial_polarity(Polarity::Positive) => [
+        ([1,0,1], [
+            SigElement::new(0.0,1.0,-2.0),
+            SigElement::new(1.0,2.0, 0.0),
+            SigElement::new(2.0,3.0, 2.0),
+        ]),
+    ]);
 
-    crate::test_len_case!(test_ami_len4_cases: Ami::new() => [
+    test_len_case!(test_ami_len4_cases: Ami::new() => [
         ([0,0,0,0], [
             SigElement::new(0.0,1.0, 0.0),
             SigElement::new(1.0,2.0, 0.0),
@@ -72,7 +123,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_ami_len6_cases: Ami::new() => [
+    test_len_case!(test_ami_len6_cases: Ami::new() => [
         ([0,1,1,1,1,0], [
             SigElement::new(0.0,1.0, 0.0),
             SigElement::new(1.0,2.0, 1.0),
@@ -91,7 +142,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_ami_len8_cases: Ami::new() => [
+    test_len_case!(test_ami_len8_cases: Ami::new() => [
         ([0,0,1,1,0,0,1,1], [
             SigElement::new(0.0,1.0, 0.0),
             SigElement::new(1.0,2.0, 0.0),
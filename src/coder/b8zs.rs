@@ -0,0 +1,327 @@
+use super::{BitCell, LineCoder, LineDecoder, Mark, Polarity, SigElement, StreamEncoder};
+
+#[derive(Debug, Clone, Copy)]
+pub struct B8zs {
+    tb: f64,
+    v: f64,
+}
+
+impl Default for B8zs {
+    fn default() -> Self {
+        Self {
+            tb: super::GLOB_BASE_TB,
+            v: -super::GLOB_BASE_V,
+        }
+    }
+}
+
+impl B8zs {
+    const RUN_LEN: usize = 8;
+
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_polarity(mut self, pol: Polarity) -> Self {
+        self.v = self.v.abs() * pol.sign();
+        self
+    }
+}
+
+// Zeros are held back until a mark or an eighth zero decides whether they
+// are sent as they are or replaced by 000VB0VB.
+#[derive(Debug, Clone)]
+struct B8zsStream {
+    coder: B8zs,
+    lvl: f64,
+    zeros: usize,
+    t: f64,
+}
+
+impl B8zsStream {
+    fn new(coder: B8zs) -> Self {
+        Self {
+            coder,
+            lvl: coder.v,
+            zeros: 0,
+            t: 0.0,
+        }
+    }
+
+    fn step(&mut self, bit: u8, lvls: &mut Vec<(f64, Mark)>) {
+        let (d, v, b) = (Mark::Data, Mark::Violation, Mark::Balancing);
+        if bit == 1 {
+            self.release(lvls);
+            self.lvl = -self.lvl;
+            lvls.push((self.lvl, d));
+            return;
+        }
+
+        self.zeros += 1;
+        if self.zeros == B8zs::RUN_LEN {
+            // 000VB0VB: both violations repeat the last mark, so the
+            // polarity left after the substitution is the one before it.
+            let lvl = self.lvl;
+            lvls.extend([
+                (0.0, d),
+                (0.0, d),
+                (0.0, d),
+                (lvl, v),
+                (-lvl, b),
+                (0.0, d),
+                (-lvl, v),
+                (lvl, b),
+            ]);
+            self.zeros = 0;
+        }
+    }
+
+    fn release(&mut self, lvls: &mut Vec<(f64, Mark)>) {
+        lvls.extend((0..self.zeros).map(|_| (0.0, Mark::Data)));
+        self.zeros = 0;
+    }
+
+    fn emit(&mut self, lvls: &[(f64, Mark)], out: &mut Vec<SigElement>) {
+        for &(lvl, _) in lvls {
+            let tf = self.t + self.coder.tb;
+            if tf > self.t {
+                out.push(SigElement::new(self.t, tf, lvl));
+            }
+            self.t += self.coder.tb;
+        }
+    }
+}
+
+impl StreamEncoder for B8zsStream {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<SigElement>) {
+        let mut lvls = Vec::with_capacity(bits.len());
+        for &bit in bits {
+            self.step(bit, &mut lvls);
+        }
+        self.emit(&lvls, out);
+    }
+
+    fn finish(&mut self, out: &mut Vec<SigElement>) {
+        let mut lvls = Vec::with_capacity(self.zeros);
+        self.release(&mut lvls);
+        self.emit(&lvls, out);
+    }
+
+    #[inline]
+    fn boxed_clone(&self) -> Box<dyn StreamEncoder> {
+        Box::new(self.clone())
+    }
+}
+
+impl B8zs {
+    fn levels(&self, bits: &[u8]) -> Vec<(f64, Mark)> {
+        let mut stream = B8zsStream::new(*self);
+        let mut lvls = Vec::with_capacity(bits.len());
+        for &bit in bits {
+            stream.step(bit, &mut lvls);
+        }
+        stream.release(&mut lvls);
+        lvls
+    }
+}
+
+impl LineCoder for B8zs {
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Box::new(B8zsStream::new(*self))
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+
+    fn cells(&self, bits: &[u8]) -> Box<[BitCell]> {
+        self.levels(bits)
+            .into_iter()
+            .zip(bits)
+            .enumerate()
+            .map(|(i, ((_, mark), &bit))| {
+                BitCell::new(i as f64 * self.tb, (i + 1) as f64 * self.tb, bit, mark)
+            })
+            .collect()
+    }
+}
+
+impl LineDecoder for B8zs {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let thr = self.v.abs() / 2.0;
+        let lvls = super::probe(sigs, self.tb, 0.5).collect::<Box<[_]>>();
+        let mut last = self.v;
+        let mut out = vec![0; lvls.len()];
+        let mut i = 0;
+
+        while i < lvls.len() {
+            let lvl = lvls[i];
+            if lvl.abs() <= thr {
+                i += 1;
+                continue;
+            }
+
+            if (lvl > 0.0) == (last > 0.0) {
+                i += 4;
+                last = lvls.get(i).copied().unwrap_or(lvl);
+                i += 1;
+                continue;
+            }
+
+            out[i] = 1;
+            last = lvl;
+            i += 1;
+        }
+
+        out.into_boxed_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coder::{LineCoder, Mark, Polarity, SigElement, b8zs::B8zs};
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    test_round_trip!(test_b8zs_round_trip: B8zs::new());
+
+    test_round_trip!(test_b8zs_configured_round_trip: B8zs::new()
+        .with_bit_rate(1e3)
+        .with_initial_polarity(Polarity::Positive));
+
+    test_stream!(test_b8zs_stream: B8zs::new());
+
+    test_len_case!(test_b8zs_len8_cases: B8zs::new() => [
+        ([0,0,0,0,0,0,0,0], [
+            SigElement::new(0.0,1.0, 0.0),
+            SigElement::new(1.0,2.0, 0.0),
+            SigElement::new(2.0,3.0, 0.0),
+            SigElement::new(3.0,4.0,-1.0),
+            SigElement::new(4.0,5.0, 1.0),
+            SigElement::new(5.0,6.0, 0.0),
+            SigElement::new(6.0,7.0, 1.0),
+            SigElement::new(7.0,8.0,-1.0),
+        ]),
+        ([1,1,0,0,0,0,1,1], [
+            SigElement::new(0.0,1.0, 1.0),
+            SigElement::new(1.0,2.0,-1.0),
+            SigElement::new(2.0,3.0, 0.0),
+            SigElement::new(3.0,4.0, 0.0),
+            SigElement::new(4.0,5.0, 0.0),
+            SigElement::new(5.0,6.0, 0.0),
+            SigElement::new(6.0,7.0, 1.0),
+            SigElement::new(7.0,8.0,-1.0),
+        ]),
+    ]);
+
+    #[test]
+    fn test_b8zs_polarity_carry_over() {
+        let seq = [1, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let expected = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, 0.0),
+            SigElement::new(2.0, 3.0, 0.0),
+            SigElement::new(3.0, 4.0, 0.0),
+            SigElement::new(4.0, 5.0, 1.0),
+            SigElement::new(5.0, 6.0, -1.0),
+            SigElement::new(6.0, 7.0, 0.0),
+            SigElement::new(7.0, 8.0, -1.0),
+            SigElement::new(8.0, 9.0, 1.0),
+            SigElement::new(9.0, 10.0, -1.0),
+        ];
+
+        let enc = B8zs::new();
+        assert_eq!(enc.encode(&seq).as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_b8zs_back_to_back_substitutions() {
+        let seq = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let expected = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, 0.0),
+            SigElement::new(2.0, 3.0, 0.0),
+            SigElement::new(3.0, 4.0, 0.0),
+            SigElement::new(4.0, 5.0, 1.0),
+            SigElement::new(5.0, 6.0, -1.0),
+            SigElement::new(6.0, 7.0, 0.0),
+            SigElement::new(7.0, 8.0, -1.0),
+            SigElement::new(8.0, 9.0, 1.0),
+            SigElement::new(9.0, 10.0, 0.0),
+            SigElement::new(10.0, 11.0, 0.0),
+            SigElement::new(11.0, 12.0, 0.0),
+            SigElement::new(12.0, 13.0, 1.0),
+            SigElement::new(13.0, 14.0, -1.0),
+            SigElement::new(14.0, 15.0, 0.0),
+            SigElement::new(15.0, 16.0, -1.0),
+            SigElement::new(16.0, 17.0, 1.0),
+        ];
+
+        let enc = B8zs::new();
+        assert_eq!(enc.encode(&seq).as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_b8zs_seven_zeroes_untouched() {
+        let seq = [1, 0, 0, 0, 0, 0, 0, 0, 1];
+        let expected = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, 0.0),
+            SigElement::new(2.0, 3.0, 0.0),
+            SigElement::new(3.0, 4.0, 0.0),
+            SigElement::new(4.0, 5.0, 0.0),
+            SigElement::new(5.0, 6.0, 0.0),
+            SigElement::new(6.0, 7.0, 0.0),
+            SigElement::new(7.0, 8.0, 0.0),
+            SigElement::new(8.0, 9.0, -1.0),
+        ];
+
+        let enc = B8zs::new();
+        assert_eq!(enc.encode(&seq).as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_b8zs_unarios_len1() {
+        let enc = B8zs::new();
+
+        let s0 = [0u8; 1];
+        let e0 = [SigElement::new(0.0, 1.0, 0.0)];
+        assert_eq!(enc.encode(&s0).as_ref(), &e0);
+
+        let s1 = [1u8; 1];
+        let e1 = [SigElement::new(0.0, 1.0, 1.0)];
+        assert_eq!(enc.encode(&s1).as_ref(), &e1);
+    }
+
+    #[test]
+    fn test_b8zs_cells_mark_substitutions() {
+        let bits = [1, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let cells = B8zs::new().cells(&bits);
+        let (d, v, b) = (Mark::Data, Mark::Violation, Mark::Balancing);
+
+        assert_eq!(
+            cells.iter().map(|cell| cell.mark()).collect::<Vec<_>>(),
+            [d, d, d, d, v, b, d, v, b, d]
+        );
+        assert_eq!(
+            cells.iter().map(|cell| cell.bit()).collect::<Vec<_>>(),
+            bits
+        );
+        assert_eq!(cells[4].ti(), 4.0);
+    }
+}
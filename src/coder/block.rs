@@ -0,0 +1,224 @@
+pub mod b4b5b;
+pub mod b8b10b;
+
+use super::{BitCell, LineCoder, LineDecoder, SigElement, StreamEncoder};
+
+pub trait BlockStream: 'static {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<u8>);
+
+    fn finish(&mut self, out: &mut Vec<u8>);
+
+    fn boxed_clone(&self) -> Box<dyn BlockStream>;
+}
+
+impl Clone for Box<dyn BlockStream> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.boxed_clone()
+    }
+}
+
+pub trait BlockCoder: 'static {
+    fn stream(&self) -> Box<dyn BlockStream>;
+
+    fn encode(&self, bits: &[u8]) -> Box<[u8]> {
+        let mut enc = self.stream();
+        let mut out = Vec::new();
+        enc.push(bits, &mut out);
+        enc.finish(&mut out);
+        out.into_boxed_slice()
+    }
+
+    fn decode(&self, bits: &[u8]) -> Box<[u8]>;
+
+    fn boxed(self) -> Box<dyn BlockCoder + 'static>
+    where
+        Self: Sized,
+    {
+        Box::new(self)
+    }
+}
+
+pub struct Composed {
+    block: Box<dyn BlockCoder>,
+    line: Box<dyn LineCoder>,
+}
+
+impl Composed {
+    #[inline]
+    pub fn new(block: Box<dyn BlockCoder>, line: Box<dyn LineCoder>) -> Self {
+        Self { block, line }
+    }
+}
+
+#[derive(Clone)]
+struct ComposedStream {
+    block: Box<dyn BlockStream>,
+    line: Box<dyn StreamEncoder>,
+    coded: Vec<u8>,
+}
+
+impl StreamEncoder for ComposedStream {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<SigElement>) {
+        self.coded.clear();
+        self.block.push(bits, &mut self.coded);
+        self.line.push(&self.coded, out);
+    }
+
+    fn finish(&mut self, out: &mut Vec<SigElement>) {
+        self.coded.clear();
+        self.block.finish(&mut self.coded);
+        self.line.push(&self.coded, out);
+        self.line.finish(out);
+    }
+
+    #[inline]
+    fn boxed_clone(&self) -> Box<dyn StreamEncoder> {
+        Box::new(self.clone())
+    }
+}
+
+impl LineCoder for Composed {
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Box::new(ComposedStream {
+            block: self.block.stream(),
+            line: self.line.stream(),
+            coded: Vec::new(),
+        })
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.line.bit_time()
+    }
+
+    #[inline]
+    fn cells(&self, bits: &[u8]) -> Box<[BitCell]> {
+        self.line.cells(&self.block.encode(bits))
+    }
+}
+
+impl LineDecoder for Composed {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        self.block.decode(&self.line.decode(sigs))
+    }
+}
+
+// Both block codes map fixed-size chunks, so their streams hold back a
+// partial chunk and only pad it with zeros once the input ends.
+trait Chunk<const N: usize>: Clone + 'static {
+    fn code(&mut self, chunk: [u8; N], out: &mut Vec<u8>);
+}
+
+#[derive(Debug, Clone)]
+struct Chunked<C, const N: usize> {
+    coder: C,
+    held: Vec<u8>,
+}
+
+impl<C: Chunk<N>, const N: usize> Chunked<C, N> {
+    #[inline]
+    fn boxed(coder: C) -> Box<dyn BlockStream> {
+        Box::new(Self {
+            coder,
+            held: Vec::with_capacity(N),
+        })
+    }
+}
+
+impl<C: Chunk<N>, const N: usize> BlockStream for Chunked<C, N> {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<u8>) {
+        for &bit in bits {
+            self.held.push(bit);
+            if self.held.len() == N {
+                let chunk = std::array::from_fn(|i| self.held[i]);
+                self.held.clear();
+                self.coder.code(chunk, out);
+            }
+        }
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) {
+        if self.held.is_empty() {
+            return;
+        }
+
+        let mut chunk = [0; N];
+        chunk[..self.held.len()].copy_from_slice(&self.held);
+        self.held.clear();
+        self.coder.code(chunk, out);
+    }
+
+    #[inline]
+    fn boxed_clone(&self) -> Box<dyn BlockStream> {
+        Box::new(self.clone())
+    }
+}
+
+#[inline]
+fn to_word(bits: &[u8]) -> u16 {
+    bits.iter()
+        .fold(0, |acc, &bit| (acc << 1) | (bit & 1) as u16)
+}
+
+#[inline]
+fn push_word(out: &mut Vec<u8>, word: u16, width: u32) {
+    out.extend((0..width).rev().map(|i| ((word >> i) & 1) as u8));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockCoder, Composed, b4b5b::B4b5b, b8b10b::B8b10b};
+    use crate::coder::{LineCoder, LineDecoder, SigElement, hdb3::Hdb3, mlt3::Mlt3, nrz::Nrzl};
+    use crate::util::testing::test_stream;
+
+    #[test]
+    fn test_composed_feeds_block_output_to_line_coder() {
+        let enc = Composed::new(B4b5b::new().boxed(), Nrzl::new().boxed());
+        let expected = [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, 1.0),
+            SigElement::new(2.0, 3.0, 1.0),
+            SigElement::new(3.0, 4.0, 1.0),
+            SigElement::new(4.0, 5.0, -1.0),
+        ];
+
+        assert_eq!(enc.encode(&[0, 0, 0, 0]).as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_composed_cells_follow_coded_bits() {
+        let enc = Composed::new(B4b5b::new().boxed(), Nrzl::new().boxed());
+        let cells = enc.cells(&[0, 0, 0, 0]);
+
+        assert_eq!(
+            cells.iter().map(|cell| cell.bit()).collect::<Vec<_>>(),
+            [1, 1, 1, 1, 0]
+        );
+        assert_eq!(cells.last().map(|cell| cell.tf()), Some(5.0));
+    }
+
+    test_stream!(test_composed_4b5b_stream: Composed::new(
+        B4b5b::new().boxed(),
+        Mlt3::new().boxed()
+    ));
+
+    test_stream!(test_composed_8b10b_stream: Composed::new(
+        B8b10b::new().boxed(),
+        Hdb3::new().boxed()
+    ));
+
+    #[test]
+    fn test_composed_round_trip() {
+        let encs = [
+            Composed::new(B4b5b::new().boxed(), Mlt3::new().boxed()),
+            Composed::new(B8b10b::new().boxed(), Nrzl::new().boxed()),
+        ];
+
+        for seq in crate::util::random_bit_strings().filter(|seq| seq.len() % 8 == 0) {
+            for enc in &encs {
+                assert_eq!(enc.decode(&enc.encode(&seq)), seq, "{seq:?}");
+            }
+        }
+    }
+}
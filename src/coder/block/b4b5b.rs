@@ -0,0 +1,80 @@
+use super::{BlockCoder, BlockStream, Chunk, Chunked};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct B4b5b;
+
+impl B4b5b {
+    const DATA: [u16; 16] = [
+        0b11110, 0b01001, 0b10100, 0b10101, 0b01010, 0b01011, 0b01110, 0b01111, 0b10010, 0b10011,
+        0b10110, 0b10111, 0b11010, 0b11011, 0b11100, 0b11101,
+    ];
+
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Chunk<4> for B4b5b {
+    #[inline]
+    fn code(&mut self, nibble: [u8; 4], out: &mut Vec<u8>) {
+        super::push_word(out, Self::DATA[super::to_word(&nibble) as usize], 5);
+    }
+}
+
+impl BlockCoder for B4b5b {
+    #[inline]
+    fn stream(&self) -> Box<dyn BlockStream> {
+        Chunked::boxed(*self)
+    }
+
+    fn decode(&self, bits: &[u8]) -> Box<[u8]> {
+        let mut out = Vec::with_capacity(bits.len() / 5 * 4);
+        for code in bits.chunks_exact(5) {
+            let code = super::to_word(code);
+            let nibble = Self::DATA
+                .iter()
+                .position(|&c| c == code)
+                .unwrap_or_default();
+            super::push_word(&mut out, nibble as u16, 4);
+        }
+
+        out.into_boxed_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::B4b5b;
+    use crate::coder::block::BlockCoder;
+
+    #[test]
+    fn test_4b5b_nibbles() {
+        let enc = B4b5b::new();
+        for (seq, exp) in [
+            ([0, 0, 0, 0], [1, 1, 1, 1, 0]),
+            ([0, 0, 0, 1], [0, 1, 0, 0, 1]),
+            ([0, 1, 1, 1], [0, 1, 1, 1, 1]),
+            ([1, 0, 0, 0], [1, 0, 0, 1, 0]),
+            ([1, 1, 1, 1], [1, 1, 1, 0, 1]),
+        ] {
+            assert_eq!(enc.encode(&seq).as_ref(), &exp);
+        }
+    }
+
+    #[test]
+    fn test_4b5b_decode_inverts_encode() {
+        let enc = B4b5b::new();
+        let seq = [0, 0, 0, 0, 1, 1, 1, 1, 0, 1, 1, 0, 1, 0, 0, 1];
+        assert_eq!(enc.decode(&enc.encode(&seq)).as_ref(), &seq);
+    }
+
+    #[test]
+    fn test_4b5b_pads_trailing_nibble() {
+        let enc = B4b5b::new();
+        assert_eq!(
+            enc.encode(&[1, 0, 1, 0, 1]).as_ref(),
+            &[1, 0, 1, 1, 0, 1, 0, 0, 1, 0]
+        );
+    }
+}
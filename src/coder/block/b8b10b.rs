@@ -0,0 +1,300 @@
+use super::{BlockCoder, BlockStream, Chunk, Chunked};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Disparity {
+    #[default]
+    Negative,
+    Positive,
+}
+
+impl Disparity {
+    #[inline]
+    fn flip(self) -> Self {
+        match self {
+            Self::Negative => Self::Positive,
+            Self::Positive => Self::Negative,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    Data(u8),
+    Control(u8),
+}
+
+impl Symbol {
+    pub const K28_5: Self = Self::Control(0xBC);
+
+    pub const fn control(x: u8, y: u8) -> Option<Self> {
+        match (x, y) {
+            (28, 0..=7) | (23 | 27 | 29 | 30, 7) => Some(Self::Control((y << 5) | x)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct B8b10b {
+    rd: Disparity,
+}
+
+impl B8b10b {
+    const D6: [u16; 32] = [
+        0b100111, 0b011101, 0b101101, 0b110001, 0b110101, 0b101001, 0b011001, 0b111000, 0b111001,
+        0b100101, 0b010101, 0b110100, 0b001101, 0b101100, 0b011100, 0b010111, 0b011011, 0b100011,
+        0b010011, 0b110010, 0b001011, 0b101010, 0b011010, 0b111010, 0b110011, 0b100110, 0b010110,
+        0b110110, 0b001110, 0b101110, 0b011110, 0b101011,
+    ];
+    const K28_6: u16 = 0b001111;
+    const D4: [u16; 8] = [
+        0b1011, 0b1001, 0b0101, 0b1100, 0b1101, 0b1010, 0b0110, 0b1110,
+    ];
+    const D4_A7: u16 = 0b0111;
+    const K4: [u16; 8] = [
+        0b1011, 0b0110, 0b1010, 0b1100, 0b1101, 0b0101, 0b1001, 0b0111,
+    ];
+
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_disparity(rd: Disparity) -> Self {
+        Self { rd }
+    }
+
+    pub fn encode_symbols(&self, syms: &[Symbol]) -> Box<[u8]> {
+        let mut rd = self.rd;
+        let mut out = Vec::with_capacity(syms.len() * 10);
+        for &sym in syms {
+            let (six, four) = Self::encode_symbol(sym, &mut rd);
+            super::push_word(&mut out, six, 6);
+            super::push_word(&mut out, four, 4);
+        }
+
+        out.into_boxed_slice()
+    }
+
+    fn encode_symbol(sym: Symbol, rd: &mut Disparity) -> (u16, u16) {
+        let (byte, is_ctrl) = match sym {
+            Symbol::Data(byte) => (byte, false),
+            Symbol::Control(byte) => (byte, true),
+        };
+        let x = (byte & 0x1F) as usize;
+        let y = (byte >> 5) as usize;
+
+        let six = if is_ctrl && x == 28 {
+            Self::K28_6
+        } else {
+            Self::D6[x]
+        };
+        let six = Self::apply_disparity(six, 6, six == 0b111000, rd);
+
+        let (four, alternates) = if is_ctrl {
+            (Self::K4[y], true)
+        } else if y == 7
+            && match rd {
+                Disparity::Negative => matches!(x, 17 | 18 | 20),
+                Disparity::Positive => matches!(x, 11 | 13 | 14),
+            }
+        {
+            (Self::D4_A7, false)
+        } else {
+            (Self::D4[y], y == 3)
+        };
+        let four = Self::apply_disparity(four, 4, alternates, rd);
+
+        (six, four)
+    }
+
+    // Inverse of `encode_symbols`, with `None` for words that are not valid
+    // symbols.
+    pub fn decode_symbols(&self, bits: &[u8]) -> Box<[Option<Symbol>]> {
+        bits.chunks_exact(10)
+            .map(|code| Self::decode_symbol(super::to_word(&code[..6]), super::to_word(&code[6..])))
+            .collect()
+    }
+
+    // Control symbols are told apart by their K28 sub-block, or by an A7
+    // sub-block after one of the x that send P7 as data.
+    fn decode_symbol(six: u16, four: u16) -> Option<Symbol> {
+        let unbalanced = |code: u16, width: u32| code.count_ones() * 2 != width;
+        let comp = |code: u16, width: u32| !code & ((1 << width) - 1);
+
+        // The K28 sub-block flips the disparity, so the next one comes
+        // complemented after 001111.
+        if six == Self::K28_6 || six == comp(Self::K28_6, 6) {
+            let y = Self::K4
+                .iter()
+                .position(|&c| four == if six == Self::K28_6 { comp(c, 4) } else { c })?;
+            return Symbol::control(28, y as u8);
+        }
+
+        let x = Self::D6
+            .iter()
+            .position(|&c| c == six || ((unbalanced(c, 6) || c == 0b111000) && comp(c, 6) == six))?
+            as u8;
+        if four == Self::D4_A7 || four == comp(Self::D4_A7, 4) {
+            return Symbol::control(x, 7).or(Some(Symbol::Data((7 << 5) | x)));
+        }
+        let y = Self::D4
+            .iter()
+            .position(|&c| c == four || ((unbalanced(c, 4) || c == 0b1100) && comp(c, 4) == four))?
+            as u8;
+
+        Some(Symbol::Data((y << 5) | x))
+    }
+
+    fn apply_disparity(code: u16, width: u32, alternates: bool, rd: &mut Disparity) -> u16 {
+        let balanced = code.count_ones() * 2 == width;
+        let code = if *rd == Disparity::Positive && (alternates || !balanced) {
+            !code & ((1 << width) - 1)
+        } else {
+            code
+        };
+
+        if !balanced {
+            *rd = rd.flip();
+        }
+        code
+    }
+}
+
+// The running disparity is carried from one byte to the next.
+impl Chunk<8> for B8b10b {
+    fn code(&mut self, byte: [u8; 8], out: &mut Vec<u8>) {
+        let sym = Symbol::Data(super::to_word(&byte) as u8);
+        let (six, four) = Self::encode_symbol(sym, &mut self.rd);
+        super::push_word(out, six, 6);
+        super::push_word(out, four, 4);
+    }
+}
+
+impl BlockCoder for B8b10b {
+    #[inline]
+    fn stream(&self) -> Box<dyn BlockStream> {
+        Chunked::boxed(*self)
+    }
+
+    fn decode(&self, bits: &[u8]) -> Box<[u8]> {
+        let mut out = Vec::with_capacity(bits.len() / 10 * 8);
+        // Control symbols and invalid words carry no data, and leave a zero
+        // byte so the bytes after them keep their place.
+        for sym in self.decode_symbols(bits) {
+            let byte = match sym {
+                Some(Symbol::Data(byte)) => byte,
+                _ => 0,
+            };
+            super::push_word(&mut out, byte as u16, 8);
+        }
+
+        out.into_boxed_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{B8b10b, Disparity, Symbol};
+    use crate::coder::block::BlockCoder;
+
+    fn word(bits: &[u8]) -> String {
+        bits.iter().map(|b| char::from(b'0' + b)).collect()
+    }
+
+    #[test]
+    fn test_8b10b_comma_alternates_disparity() {
+        let enc = B8b10b::new();
+        let out = enc.encode_symbols(&[Symbol::K28_5, Symbol::K28_5, Symbol::K28_5]);
+        assert_eq!(word(&out[..10]), "0011111010");
+        assert_eq!(word(&out[10..20]), "1100000101");
+        assert_eq!(word(&out[20..]), "0011111010");
+    }
+
+    #[test]
+    fn test_8b10b_data_vectors() {
+        let enc = B8b10b::new();
+        for (byte, rd, exp) in [
+            (0x00, Disparity::Negative, "1001110100"),
+            (0x00, Disparity::Positive, "0110001011"),
+            (0xB5, Disparity::Negative, "1010101010"),
+            (0x03, Disparity::Negative, "1100011011"),
+            (0x03, Disparity::Positive, "1100010100"),
+            (0x07, Disparity::Negative, "1110001011"),
+            (0xF1, Disparity::Negative, "1000110111"),
+            (0xF1, Disparity::Positive, "1000110001"),
+            (0xF4, Disparity::Negative, "0010110111"),
+            (0xEB, Disparity::Positive, "1101001000"),
+            (0xFF, Disparity::Negative, "1010110001"),
+        ] {
+            let out = B8b10b::with_disparity(rd).encode_symbols(&[Symbol::Data(byte)]);
+            assert_eq!(word(&out), exp, "D{}.{} {rd:?}", byte & 0x1F, byte >> 5);
+        }
+
+        assert_eq!(word(&enc.encode(&[1, 0, 1, 1, 0, 1, 0, 1])), "1010101010");
+    }
+
+    #[test]
+    fn test_8b10b_decode_inverts_encode() {
+        for rd in [Disparity::Negative, Disparity::Positive] {
+            let enc = B8b10b::with_disparity(rd);
+            let seq = (0..=255u8)
+                .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+                .collect::<Box<[_]>>();
+            assert_eq!(enc.decode(&enc.encode(&seq)), seq);
+        }
+    }
+
+    #[test]
+    fn test_8b10b_control_codes() {
+        assert_eq!(Symbol::control(28, 5), Some(Symbol::K28_5));
+        assert_eq!(Symbol::control(21, 5), None);
+        assert_eq!(Symbol::control(23, 6), None);
+
+        let out = B8b10b::new().encode_symbols(&[
+            Symbol::control(28, 1).unwrap(),
+            Symbol::control(23, 7).unwrap(),
+        ]);
+        assert_eq!(word(&out[..10]), "0011111001");
+        assert_eq!(word(&out[10..]), "0001010111");
+    }
+
+    #[test]
+    fn test_8b10b_decode_control_codes() {
+        let ctrls = (0..8)
+            .filter_map(|y| Symbol::control(28, y))
+            .chain([23, 27, 29, 30].map(|x| Symbol::control(x, 7).unwrap()))
+            .collect::<Box<[_]>>();
+        for rd in [Disparity::Negative, Disparity::Positive] {
+            let enc = B8b10b::with_disparity(rd);
+            let syms = ctrls
+                .iter()
+                .copied()
+                .chain((0..=255).map(Symbol::Data))
+                .collect::<Box<[_]>>();
+            let out = enc
+                .decode_symbols(&enc.encode_symbols(&syms))
+                .iter()
+                .map(|sym| sym.unwrap())
+                .collect::<Box<[_]>>();
+            assert_eq!(out, syms, "{rd:?}");
+        }
+
+        let enc = B8b10b::new();
+        let k28_1 = enc.encode_symbols(&[Symbol::control(28, 1).unwrap()]);
+        assert_eq!(enc.decode(&k28_1).as_ref(), &[0; 8]);
+        assert_eq!(enc.decode_symbols(&[0; 10]).as_ref(), &[None]);
+    }
+
+    #[test]
+    fn test_8b10b_stays_dc_balanced() {
+        let enc = B8b10b::new();
+        let syms = (0..=255).map(Symbol::Data).collect::<Box<[_]>>();
+        let mut rds = -1i32;
+        for bit in enc.encode_symbols(&syms) {
+            rds += if bit == 1 { 1 } else { -1 };
+            assert!((-3..=3).contains(&rds));
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use strum::IntoEnumIterator;
+use strum_macros::{AsRefStr, EnumIter, FromRepr};
+
+use super::{
+    LineCoder,
+    block::{BlockCoder, Composed, b4b5b::B4b5b, b8b10b::B8b10b},
+    registry::Entry,
+};
+
+#[derive(Debug, Clone, Default, Copy, PartialEq, Eq, EnumIter, AsRefStr, FromRepr)]
+pub enum BlockName {
+    #[default]
+    #[strum(serialize = "None")]
+    None,
+    #[strum(serialize = "4B/5B")]
+    B4b5b,
+    #[strum(serialize = "8B/10B")]
+    B8b10b,
+}
+
+impl BlockName {
+    pub fn id(self) -> &'static str {
+        match self {
+            BlockName::None => "none",
+            BlockName::B4b5b => "4b5b",
+            BlockName::B8b10b => "8b10b",
+        }
+    }
+
+    #[inline]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::iter().find(|name| name.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn to_raw(self) -> Option<Box<dyn BlockCoder>> {
+        match self {
+            BlockName::None => None,
+            BlockName::B4b5b => Some(B4b5b::new().boxed()),
+            BlockName::B8b10b => Some(B8b10b::new().boxed()),
+        }
+    }
+
+    #[inline]
+    pub fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub bit_rate: f64,
+    pub amplitude: f64,
+    pub init_state: usize,
+    pub duty: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            bit_rate: 1.0,
+            amplitude: 1.0,
+            init_state: 0,
+            duty: 0.5,
+        }
+    }
+}
+
+pub fn build(entry: &Entry, block_name: BlockName, settings: &Settings) -> Box<dyn LineCoder> {
+    match block_name.to_raw() {
+        Some(block) => Composed::new(block, entry.build(settings)).boxed(),
+        None => entry.build(settings),
+    }
+}
+
+pub fn label(entry: &Entry, block_name: BlockName) -> String {
+    match block_name {
+        BlockName::None => entry.name().to_owned(),
+        _ => format!("{} + {}", block_name.as_ref(), entry.name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::BlockName;
+
+    #[test]
+    fn test_ids_resolve_back_to_names() {
+        for name in BlockName::iter() {
+            assert_eq!(BlockName::from_id(name.id()), Some(name));
+        }
+        assert_eq!(BlockName::from_id("4B5B"), Some(BlockName::B4b5b));
+        assert_eq!(BlockName::from_id("4b6b"), None);
+    }
+}
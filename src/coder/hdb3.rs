@@ -1,4 +1,4 @@
-use super::{LineCoder, SigElement};
+use super::{BitCell, LineCoder, LineDecoder, Mark, Polarity, SigElement, StreamEncoder};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Hdb3 {
@@ -20,6 +20,24 @@ impl Hdb3 {
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_polarity(mut self, pol: Polarity) -> Self {
+        self.v = self.v.abs() * pol.sign();
+        self
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -29,74 +47,168 @@ enum Symbol {
     Silence,
 }
 
-impl LineCoder for Hdb3 {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let mut syms = Vec::new();
-        let mut zero_count = 0;
-        let mut marks = 0;
-        let mut lm_pol = if self.v.is_sign_negative() {
-            -self.v
+impl Symbol {
+    #[inline]
+    fn of(pol: f64) -> Self {
+        if pol > 0.0 {
+            Symbol::Positive
         } else {
-            self.v
-        };
+            Symbol::Negative
+        }
+    }
+}
 
-        for &bit in bits {
-            if bit == 1 {
-                lm_pol = -lm_pol;
-                syms.push(if lm_pol > 0.0 {
-                    Symbol::Positive
-                } else {
-                    Symbol::Negative
-                });
-
-                marks += 1;
-                zero_count = 0;
-                continue;
-            }
+// Zeros are held back until a mark or a fourth zero decides whether they are
+// sent as they are or replaced by 000V or B00V.
+#[derive(Debug, Clone)]
+struct Hdb3Stream {
+    coder: Hdb3,
+    lm_pol: f64,
+    marks: usize,
+    zeros: usize,
+    t: f64,
+}
 
-            zero_count += 1;
-            if zero_count == 4 {
-                syms.truncate(syms.len().saturating_sub(zero_count - 1));
-                if (marks & 1) != 0 {
-                    syms.extend((0..(zero_count - 1)).map(|_| Symbol::Silence));
-                    syms.push(if lm_pol > 0.0 {
-                        Symbol::Positive
-                    } else {
-                        Symbol::Negative
-                    });
-                } else {
-                    lm_pol = -lm_pol;
-                    let pol_mark = if lm_pol > 0.0 {
-                        Symbol::Positive
-                    } else {
-                        Symbol::Negative
-                    };
-
-                    syms.push(pol_mark);
-                    syms.extend((0..(zero_count / 2)).map(|_| Symbol::Silence));
-                    syms.push(pol_mark);
-                }
-
-                marks = 0;
-                zero_count = 0;
-            } else {
-                syms.push(Symbol::Silence);
-            }
+impl Hdb3Stream {
+    const RUN_LEN: usize = 4;
+
+    fn new(coder: Hdb3) -> Self {
+        Self {
+            coder,
+            lm_pol: coder.v.abs(),
+            marks: 0,
+            zeros: 0,
+            t: 0.0,
         }
+    }
 
-        let mut out = Vec::new();
-        let mut t = 0.0;
-        for sym in syms {
-            let tf = t + self.tb;
-            if tf > t {
+    fn step(&mut self, bit: u8, syms: &mut Vec<(Symbol, Mark)>) {
+        if bit == 1 {
+            self.release(syms);
+            self.lm_pol = -self.lm_pol;
+            syms.push((Symbol::of(self.lm_pol), Mark::Data));
+            self.marks += 1;
+            return;
+        }
+
+        self.zeros += 1;
+        if self.zeros < Self::RUN_LEN {
+            return;
+        }
+
+        if (self.marks & 1) != 0 {
+            syms.extend([(Symbol::Silence, Mark::Data); Self::RUN_LEN - 1]);
+            syms.push((Symbol::of(self.lm_pol), Mark::Violation));
+        } else {
+            self.lm_pol = -self.lm_pol;
+            let pol_mark = Symbol::of(self.lm_pol);
+            syms.push((pol_mark, Mark::Balancing));
+            syms.extend([(Symbol::Silence, Mark::Data); Self::RUN_LEN / 2]);
+            syms.push((pol_mark, Mark::Violation));
+        }
+
+        self.marks = 0;
+        self.zeros = 0;
+    }
+
+    fn release(&mut self, syms: &mut Vec<(Symbol, Mark)>) {
+        syms.extend((0..self.zeros).map(|_| (Symbol::Silence, Mark::Data)));
+        self.zeros = 0;
+    }
+
+    fn emit(&mut self, syms: &[(Symbol, Mark)], out: &mut Vec<SigElement>) {
+        let Hdb3 { tb, v } = self.coder;
+        for &(sym, _) in syms {
+            let tf = self.t + tb;
+            if tf > self.t {
                 let lvl = match sym {
-                    Symbol::Positive => self.v,
-                    Symbol::Negative => -self.v,
+                    Symbol::Positive => v,
+                    Symbol::Negative => -v,
                     Symbol::Silence => 0.0,
                 };
-                out.push(SigElement::new(t, tf, lvl));
+                out.push(SigElement::new(self.t, tf, lvl));
             }
-            t += self.tb;
+            self.t += tb;
+        }
+    }
+}
+
+impl StreamEncoder for Hdb3Stream {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<SigElement>) {
+        let mut syms = Vec::with_capacity(bits.len());
+        for &bit in bits {
+            self.step(bit, &mut syms);
+        }
+        self.emit(&syms, out);
+    }
+
+    fn finish(&mut self, out: &mut Vec<SigElement>) {
+        let mut syms = Vec::with_capacity(self.zeros);
+        self.release(&mut syms);
+        self.emit(&syms, out);
+    }
+
+    #[inline]
+    fn boxed_clone(&self) -> Box<dyn StreamEncoder> {
+        Box::new(self.clone())
+    }
+}
+
+impl Hdb3 {
+    fn symbols(&self, bits: &[u8]) -> Vec<(Symbol, Mark)> {
+        let mut stream = Hdb3Stream::new(*self);
+        let mut syms = Vec::with_capacity(bits.len());
+        for &bit in bits {
+            stream.step(bit, &mut syms);
+        }
+        stream.release(&mut syms);
+        syms
+    }
+}
+
+impl LineCoder for Hdb3 {
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Box::new(Hdb3Stream::new(*self))
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+
+    fn cells(&self, bits: &[u8]) -> Box<[BitCell]> {
+        self.symbols(bits)
+            .into_iter()
+            .zip(bits)
+            .enumerate()
+            .map(|(i, ((_, mark), &bit))| {
+                BitCell::new(i as f64 * self.tb, (i + 1) as f64 * self.tb, bit, mark)
+            })
+            .collect()
+    }
+}
+
+impl LineDecoder for Hdb3 {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let thr = self.v.abs() / 2.0;
+        let mut last = self.v;
+        let mut out = Vec::new();
+
+        for lvl in super::probe(sigs, self.tb, 0.5) {
+            if lvl.abs() <= thr {
+                out.push(0);
+                continue;
+            }
+
+            if (lvl > 0.0) == (last > 0.0) {
+                let n = out.len();
+                out[n.saturating_sub(3)..].fill(0);
+                out.push(0);
+            } else {
+                out.push(1);
+            }
+            last = lvl;
         }
 
         out.into_boxed_slice()
@@ -106,7 +218,16 @@ impl LineCoder for Hdb3 {
 #[cfg(test)]
 mod tests {
     use super::Hdb3;
-    use crate::coder::{LineCoder, SigElement};
+    use crate::coder::{LineCoder, Mark, Polarity, SigElement};
+    use crate::util::testing::{test_round_trip, test_stream};
+
+    test_round_trip!(test_hdb3_round_trip: Hdb3::new());
+
+    test_round_trip!(test_hdb3_configured_round_trip: Hdb3::new()
+        .with_amplitude(2.0)
+        .with_initial_polarity(Polarity::Positive));
+
+    test_stream!(test_hdb3_stream: Hdb3::new().with_initial_polarity(Polarity::Positive));
 
     #[test]
     fn test_hdb3_0000_even_to_b00v() {
@@ -227,4 +348,20 @@ mod tests {
         let e1 = [SigElement::new(0.0, 1.0, 1.0)];
         assert_eq!(enc.encode(&s1).as_ref(), &e1);
     }
+
+    #[test]
+    fn test_hdb3_cells_mark_substitutions() {
+        let marks = |bits: &[u8]| {
+            Hdb3::new()
+                .cells(bits)
+                .iter()
+                .map(|cell| cell.mark())
+                .collect::<Vec<_>>()
+        };
+        let (d, v, b) = (Mark::Data, Mark::Violation, Mark::Balancing);
+
+        assert_eq!(marks(&[1, 0, 0, 0, 0]), [d, d, d, d, v]);
+        assert_eq!(marks(&[1, 1, 0, 0, 0, 0]), [d, d, b, d, d, v]);
+        assert_eq!(marks(&[0, 0, 0, 0, 0, 0, 0, 0]), [b, d, d, v, b, d, d, v]);
+    }
 }
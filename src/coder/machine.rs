@@ -0,0 +1,478 @@
+use std::{str::FromStr, sync::Arc};
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_till, take_while1},
+    character::complete::{char, one_of, space0, space1},
+    combinator::{all_consuming, map, opt},
+    multi::separated_list1,
+    number::complete::double,
+    sequence::{delimited, preceded},
+};
+
+use super::{
+    LineCoder, LineDecoder, SigElement, StreamEncoder,
+    registry::{Entry, Schema},
+    stream::{Step, Stepper},
+};
+
+#[derive(Debug)]
+struct Transition {
+    next: usize,
+    segments: Box<[(f64, f64)]>,
+}
+
+#[derive(Debug)]
+struct State {
+    name: String,
+    out: [Transition; 2],
+}
+
+// Each bit emits the segments of the transition taken from the current
+// state, as levels relative to the amplitude over fractions of the bit time.
+#[derive(Debug, Clone)]
+pub struct StateMachine {
+    tb: f64,
+    v: f64,
+    state: usize,
+    states: Arc<[State]>,
+}
+
+impl StateMachine {
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_state(mut self, idx: usize) -> Self {
+        self.state = idx.min(self.states.len() - 1);
+        self
+    }
+
+    pub fn state_names(&self) -> impl Iterator<Item = &str> {
+        self.states.iter().map(|state| state.name.as_str())
+    }
+}
+
+impl LineCoder for StateMachine {
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(self.clone())
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+impl Step for StateMachine {
+    fn step(&mut self, bit: u8, mut t: f64, out: &mut Vec<SigElement>) {
+        let tr = &self.states[self.state].out[bit as usize & 1];
+        for &(lvl, frac) in &tr.segments {
+            let tf = t + frac * self.tb;
+            out.push(SigElement::new(t, tf, lvl * self.v));
+            t = tf;
+        }
+        self.state = tr.next;
+    }
+}
+
+impl LineDecoder for StateMachine {
+    // Picks, bit after bit, the transition whose segments are closest to the
+    // waveform sampled in the middle of each of them.
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let n = sigs
+            .last()
+            .map_or(0, |sig| (sig.tf() / self.tb).round() as usize);
+        let mut state = self.state;
+
+        (0..n)
+            .map(|k| {
+                let error = |tr: &Transition| {
+                    let mut at = 0.0;
+                    tr.segments
+                        .iter()
+                        .map(|&(lvl, frac)| {
+                            let t = (k as f64 + at + frac / 2.0) * self.tb;
+                            at += frac;
+                            (super::level_at(sigs, t) - lvl * self.v).powi(2)
+                        })
+                        .sum::<f64>()
+                };
+                let out = &self.states[state].out;
+                let bit = (error(&out[1]) < error(&out[0])) as usize;
+                state = out[bit].next;
+                bit as u8
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Definition {
+    id: String,
+    name: String,
+    description: String,
+    machine: StateMachine,
+}
+
+impl Definition {
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn machine(&self) -> &StateMachine {
+        &self.machine
+    }
+
+    pub fn entry(&self) -> Entry {
+        let machine = self.machine.clone();
+        Entry::new(self.id.clone(), self.name.clone(), move |s| {
+            machine
+                .clone()
+                .with_bit_rate(s.bit_rate)
+                .with_amplitude(s.amplitude)
+                .with_initial_state(s.init_state)
+                .boxed()
+        })
+        .with_description(self.description.clone())
+        .with_schema(Schema::new().with_init_states(self.machine.state_names(), self.machine.state))
+    }
+}
+
+enum Stmt<'a> {
+    Id(&'a str),
+    Name(&'a str),
+    Description(&'a str),
+    Start(&'a str),
+    State(&'a str),
+    Rule(u8, &'a str, Vec<(f64, Option<f64>)>),
+}
+
+fn ident(input: &str) -> IResult<&str, &str> {
+    take_while1(|ch: char| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_').parse(input)
+}
+
+fn quoted(input: &str) -> IResult<&str, &str> {
+    delimited(char('"'), take_till(|ch| ch == '"'), char('"')).parse(input)
+}
+
+fn stmt(input: &str) -> IResult<&str, Stmt<'_>> {
+    let segment = (double, opt(preceded(char('/'), double)));
+    let rule = map(
+        (
+            one_of("01"),
+            delimited(space0, tag("->"), space0),
+            ident,
+            delimited(space0, char(':'), space0),
+            separated_list1(space1, segment),
+        ),
+        |(bit, _, next, _, segments)| Stmt::Rule(bit as u8 - b'0', next, segments),
+    );
+
+    alt((
+        map(preceded((tag("id"), space1), ident), Stmt::Id),
+        map(preceded((tag("name"), space1), quoted), Stmt::Name),
+        map(
+            preceded((tag("description"), space1), quoted),
+            Stmt::Description,
+        ),
+        map(preceded((tag("start"), space1), ident), Stmt::Start),
+        map(preceded((tag("state"), space1), ident), Stmt::State),
+        rule,
+    ))
+    .parse(input)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (idx, ch) in line.char_indices() {
+        match ch {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+// Segments without a fraction share whatever time the others leave.
+fn resolve(segments: &[(f64, Option<f64>)]) -> Result<Box<[(f64, f64)]>, String> {
+    if let Some(lvl) = segments.iter().map(|s| s.0).find(|lvl| !lvl.is_finite()) {
+        return Err(format!("level `{lvl}` is not finite"));
+    }
+    if let Some(frac) = segments
+        .iter()
+        .filter_map(|s| s.1)
+        .find(|frac| !(*frac > 0.0 && *frac <= 1.0))
+    {
+        return Err(format!("fraction `{frac}` must be within (0, 1]"));
+    }
+
+    let used = segments.iter().filter_map(|s| s.1).sum::<f64>();
+    let open = segments.iter().filter(|s| s.1.is_none()).count();
+    let share = match open {
+        0 if (used - 1.0).abs() > 1e-6 => {
+            return Err(format!("fractions add up to {used}, not 1"));
+        }
+        0 => 0.0,
+        _ if used >= 1.0 - 1e-9 => {
+            return Err("no time left for the segments without a fraction".to_owned());
+        }
+        n => (1.0 - used) / n as f64,
+    };
+
+    Ok(segments
+        .iter()
+        .map(|&(lvl, frac)| (lvl, frac.unwrap_or(share)))
+        .collect())
+}
+
+impl FromStr for Definition {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        type Rule<'a> = (usize, &'a str, Box<[(f64, f64)]>);
+
+        let mut id = None;
+        let mut name = None;
+        let mut description = None;
+        let mut start = None;
+        let mut states: Vec<(&str, [Option<Rule<'_>>; 2])> = Vec::new();
+
+        for (idx, line) in src.lines().enumerate() {
+            let ln = idx + 1;
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (_, stmt) = all_consuming(stmt)
+                .parse(line)
+                .map_err(|_| format!("line {ln}: cannot parse `{line}`"))?;
+            let set = |slot: &mut Option<_>, key: &str, val| match slot.replace(val) {
+                Some(_) => Err(format!("line {ln}: `{key}` is given twice")),
+                None => Ok(()),
+            };
+            match stmt {
+                Stmt::Id(val) => set(&mut id, "id", val)?,
+                Stmt::Name(val) => set(&mut name, "name", val)?,
+                Stmt::Description(val) => set(&mut description, "description", val)?,
+                Stmt::Start(val) => set(&mut start, "start", val)?,
+                Stmt::State(state) if states.iter().any(|s| s.0 == state) => {
+                    return Err(format!("line {ln}: state `{state}` is defined twice"));
+                }
+                Stmt::State(state) => states.push((state, [None, None])),
+                Stmt::Rule(bit, next, segments) => {
+                    let Some((state, rules)) = states.last_mut() else {
+                        return Err(format!("line {ln}: transition outside of a state"));
+                    };
+                    let segments = resolve(&segments).map_err(|err| format!("line {ln}: {err}"))?;
+                    if rules[bit as usize].replace((ln, next, segments)).is_some() {
+                        return Err(format!(
+                            "line {ln}: state `{state}` has two transitions for bit {bit}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        let id = id.ok_or("missing `id`")?;
+        if states.is_empty() {
+            return Err("no states defined".to_owned());
+        }
+        let index = |name: &str| states.iter().position(|s| s.0 == name);
+        let init = match start {
+            Some(start) => index(start).ok_or_else(|| format!("unknown start state `{start}`"))?,
+            None => 0,
+        };
+
+        let compiled = states
+            .iter()
+            .map(|(state, rules)| {
+                let mut out = rules.iter().enumerate().map(|(bit, rule)| {
+                    let (ln, next, segments) = rule.as_ref().ok_or_else(|| {
+                        format!("state `{state}` has no transition for bit {bit}")
+                    })?;
+                    let next =
+                        index(next).ok_or_else(|| format!("line {ln}: unknown state `{next}`"))?;
+                    Ok::<_, String>(Transition {
+                        next,
+                        segments: segments.clone(),
+                    })
+                });
+                Ok(State {
+                    name: state.to_string(),
+                    out: [out.next().unwrap()?, out.next().unwrap()?],
+                })
+            })
+            .collect::<Result<Arc<[_]>, String>>()?;
+
+        Ok(Self {
+            id: id.to_owned(),
+            name: name.unwrap_or(id).to_owned(),
+            description: description.unwrap_or_default().to_owned(),
+            machine: StateMachine {
+                tb: super::GLOB_BASE_TB,
+                v: super::GLOB_BASE_V,
+                state: init,
+                states: compiled,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Definition;
+    use crate::coder::{
+        LineCoder, SigElement, catalog::Settings, manch::Manchester, registry::Registry, rz::Rz,
+    };
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    const AMI: &str = r#"
+        # AMI as a two state machine
+        id my-ami
+        name "My AMI"
+        description "Marks alternate, # is not a comment here"
+        start neg
+
+        state pos
+          0 -> pos : 0
+          1 -> neg : -1
+
+        state neg      # the last mark was negative
+          0 -> neg : 0
+          1 -> pos : +1
+    "#;
+
+    fn ami() -> Definition {
+        AMI.parse().unwrap()
+    }
+
+    test_len_case!(test_machine_encodes_transitions: ami().machine().clone() => [
+        ([1, 0, 1, 1], [
+            SigElement::new(0.0, 1.0, 1.0),
+            SigElement::new(1.0, 2.0, 0.0),
+            SigElement::new(2.0, 3.0, -1.0),
+            SigElement::new(3.0, 4.0, 1.0),
+        ]),
+    ]);
+
+    test_round_trip!(test_machine_round_trip: ami().machine().clone().with_amplitude(2.0));
+
+    test_stream!(test_machine_stream: ami().machine().clone());
+
+    #[test]
+    fn test_machine_matches_builtin_coders() {
+        let manch: Definition = "id m\nstate s\n0 -> s : +1 -1\n1 -> s : -1 +1"
+            .parse()
+            .unwrap();
+        let rz: Definition = "id r\nstate s\n0 -> s: -1/0.25 0\n1 -> s: 1/0.25 0"
+            .parse()
+            .unwrap();
+
+        for bits in [[1, 0, 0, 1, 1, 0], [0, 0, 0, 0, 0, 0]] {
+            assert_eq!(
+                manch.machine().encode(&bits),
+                Manchester::new().encode(&bits)
+            );
+            assert_eq!(
+                rz.machine().encode(&bits),
+                Rz::new().with_duty(0.25).encode(&bits)
+            );
+            assert_eq!(
+                manch.machine().clone().with_amplitude(-2.0).encode(&bits),
+                Manchester::new().with_amplitude(-2.0).encode(&bits)
+            );
+        }
+    }
+
+    #[test]
+    fn test_machine_entry_registers() {
+        let def = ami();
+        assert_eq!((def.id(), def.name()), ("my-ami", "My AMI"));
+
+        let mut registry = Registry::builtin();
+        assert!(registry.register(def.entry()).is_none());
+        let entry = registry.get("MY-AMI").unwrap();
+        assert_eq!(
+            entry.description(),
+            "Marks alternate, # is not a comment here"
+        );
+        assert_eq!(entry.schema().init_states(), ["pos", "neg"]);
+        assert_eq!(entry.schema().def_init_state(), 1);
+
+        let settings = Settings {
+            bit_rate: 2.0,
+            amplitude: 3.0,
+            init_state: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            entry.build(&settings).encode(&[1]).as_ref(),
+            &[SigElement::new(0.0, 0.5, -3.0)]
+        );
+    }
+
+    #[test]
+    fn test_machine_definition_errors() {
+        for (src, err) in [
+            ("state s\n0 -> s : 0\n1 -> s : 1", "missing `id`"),
+            ("id x", "no states defined"),
+            ("id x\n0 -> s : 1", "line 2: transition outside of a state"),
+            (
+                "id x\nstate s\n0 -> s : 0",
+                "state `s` has no transition for bit 1",
+            ),
+            (
+                "id x\nstate s\n0 -> s : 0\n1 -> t : 1",
+                "line 4: unknown state `t`",
+            ),
+            (
+                "id x\nstate s\n0 -> s : 0/0.5 1/0.2\n1 -> s : 1",
+                "line 3: fractions add up to 0.7, not 1",
+            ),
+            (
+                "id x\nstate s\n0 -> s : 0/1 1\n1 -> s : 1",
+                "line 3: no time left for the segments without a fraction",
+            ),
+            (
+                "id x\nstate s\n0 -> s : 0/1.5\n1 -> s : 1",
+                "line 3: fraction `1.5` must be within (0, 1]",
+            ),
+            (
+                "id x\nstate s\n0 -> s : 0\n0 -> s : 1",
+                "line 4: state `s` has two transitions for bit 0",
+            ),
+            ("id x\nid y", "line 2: `id` is given twice"),
+            (
+                "id x\nstart t\nstate s\n0 -> s : 0\n1 -> s : 1",
+                "unknown start state `t`",
+            ),
+            (
+                "id x\nstate s\n2 -> s : 0",
+                "line 3: cannot parse `2 -> s : 0`",
+            ),
+        ] {
+            assert_eq!(src.parse::<Definition>().unwrap_err(), err, "{src:?}");
+        }
+    }
+}
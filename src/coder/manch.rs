@@ -1,9 +1,13 @@
-use super::{LineCoder, SigElement};
+use super::{
+    LineCoder, LineDecoder, Polarity, SigElement, StreamEncoder,
+    stream::{Step, Stepper},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Manchester {
     tb: f64,
     v: f64,
+    duty: f64,
 }
 
 impl Default for Manchester {
@@ -11,60 +15,192 @@ impl Default for Manchester {
         Self {
             tb: super::GLOB_BASE_TB,
             v: super::GLOB_BASE_V,
+            duty: super::GLOB_BASE_DUTY,
         }
     }
 }
 
 impl Manchester {
-    const DEF_DUTY: f64 = 0.5;
-
     #[inline]
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_duty(mut self, duty: f64) -> Self {
+        self.duty = super::duty_for(duty, self.duty);
+        self
+    }
 }
 
 impl LineCoder for Manchester {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let h = self.tb * Self::DEF_DUTY;
-        let mut t = 0.0;
-        let mut out = Vec::new();
-
-        for &bit in bits {
-            if bit == 0 {
-                let t0 = t + h;
-                if t0 > t {
-                    out.push(SigElement::new(t, t0, self.v));
-                }
-
-                let t1 = t + self.tb;
-                if t1 > t0 {
-                    out.push(SigElement::new(t0, t1, -self.v));
-                }
-            } else {
-                let t0 = t + h;
-                if t0 > t {
-                    out.push(SigElement::new(t, t0, -self.v));
-                }
-
-                let t1 = t + self.tb;
-                if t1 > t0 {
-                    out.push(SigElement::new(t0, t1, self.v));
-                }
-            }
-
-            t += self.tb;
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+impl Step for Manchester {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        let lvl = if bit == 0 { self.v } else { -self.v };
+        let t0 = t + self.tb * self.duty;
+        if t0 > t {
+            out.push(SigElement::new(t, t0, lvl));
+        }
+
+        let t1 = t + self.tb;
+        if t1 > t0 {
+            out.push(SigElement::new(t0, t1, -lvl));
+        }
+    }
+}
+
+impl LineDecoder for Manchester {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let first = super::probe(sigs, self.tb, self.duty / 2.0);
+        let second = super::probe(sigs, self.tb, (1.0 + self.duty) / 2.0);
+        first.zip(second).map(|(a, b)| (b > a) as u8).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiffManchester {
+    tb: f64,
+    v: f64,
+    duty: f64,
+}
+
+impl Default for DiffManchester {
+    fn default() -> Self {
+        Self {
+            tb: super::GLOB_BASE_TB,
+            v: -super::GLOB_BASE_V,
+            duty: super::GLOB_BASE_DUTY,
+        }
+    }
+}
+
+impl DiffManchester {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_polarity(mut self, pol: Polarity) -> Self {
+        self.v = self.v.abs() * pol.sign();
+        self
+    }
+
+    #[inline]
+    pub fn with_duty(mut self, duty: f64) -> Self {
+        self.duty = super::duty_for(duty, self.duty);
+        self
+    }
+}
+
+impl LineCoder for DiffManchester {
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+// The amplitude's sign is the level the previous bit ended on.
+impl Step for DiffManchester {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        if bit == 0 {
+            self.v = -self.v;
+        }
+
+        let t0 = t + self.tb * self.duty;
+        if t0 > t {
+            out.push(SigElement::new(t, t0, self.v));
         }
 
-        out.into_boxed_slice()
+        self.v = -self.v;
+        let t1 = t + self.tb;
+        if t1 > t0 {
+            out.push(SigElement::new(t0, t1, self.v));
+        }
+    }
+}
+
+impl LineDecoder for DiffManchester {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let first = super::probe(sigs, self.tb, self.duty / 2.0);
+        let second = super::probe(sigs, self.tb, (1.0 + self.duty) / 2.0);
+        let mut prev = self.v;
+        first
+            .zip(second)
+            .map(|(a, b)| {
+                let bit = ((a > 0.0) == (prev > 0.0)) as u8;
+                prev = b;
+                bit
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::coder::{LineCoder, SigElement, manch::Manchester};
+    use crate::coder::{
+        LineCoder, Polarity, SigElement,
+        manch::{DiffManchester, Manchester},
+    };
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    test_round_trip!(test_manchester_round_trip: Manchester::new());
+
+    test_round_trip!(test_diff_manchester_round_trip: DiffManchester::new());
 
-    crate::test_len_case!(test_manchester_len4_cases: Manchester::new() => [
+    test_round_trip!(test_manchester_configured_round_trip: Manchester::new()
+        .with_bit_rate(4.0)
+        .with_duty(0.3));
+
+    test_round_trip!(test_diff_manchester_configured_round_trip: DiffManchester::new()
+        .with_duty(0.7)
+        .with_initial_polarity(Polarity::Positive));
+
+    test_stream!(test_manchester_stream: Manchester::new().with_duty(0.3));
+
+    test_stream!(test_diff_manchester_stream: DiffManchester::new().with_duty(0.7));
+
+    test_len_case!(test_manchester_len4_cases: Manchester::new() => [
         ([0,0,0,0], [
             SigElement::new(0.0,0.5, 1.0),
             SigElement::new(0.5,1.0,-1.0),
@@ -97,7 +233,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_manchester_len6_cases: Manchester::new() => [
+    test_len_case!(test_manchester_len6_cases: Manchester::new() => [
         ([0,1,1,1,1,0], [
             SigElement::new(0.0,0.5, 1.0),
             SigElement::new(0.5,1.0,-1.0),
@@ -128,7 +264,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_manchester_len8_cases: Manchester::new() => [
+    test_len_case!(test_manchester_len8_cases: Manchester::new() => [
         ([0,0,1,1,0,0,1,1], [
             SigElement::new(0.0,0.5, 1.0),
             SigElement::new(0.5,1.0,-1.0),
@@ -185,4 +321,126 @@ mod tests {
         ];
         assert_eq!(enc.encode(&s1).as_ref(), &e1);
     }
+
+    test_len_case!(test_diff_manchester_len4_cases: DiffManchester::new() => [
+        ([0,0,0,0], [
+            SigElement::new(0.0,0.5, 1.0),
+            SigElement::new(0.5,1.0,-1.0),
+            SigElement::new(1.0,1.5, 1.0),
+            SigElement::new(1.5,2.0,-1.0),
+            SigElement::new(2.0,2.5, 1.0),
+            SigElement::new(2.5,3.0,-1.0),
+            SigElement::new(3.0,3.5, 1.0),
+            SigElement::new(3.5,4.0,-1.0),
+        ]),
+        ([1,1,1,1], [
+            SigElement::new(0.0,0.5,-1.0),
+            SigElement::new(0.5,1.0, 1.0),
+            SigElement::new(1.0,1.5, 1.0),
+            SigElement::new(1.5,2.0,-1.0),
+            SigElement::new(2.0,2.5,-1.0),
+            SigElement::new(2.5,3.0, 1.0),
+            SigElement::new(3.0,3.5, 1.0),
+            SigElement::new(3.5,4.0,-1.0),
+        ]),
+        ([1,0,1,0], [
+            SigElement::new(0.0,0.5,-1.0),
+            SigElement::new(0.5,1.0, 1.0),
+            SigElement::new(1.0,1.5,-1.0),
+            SigElement::new(1.5,2.0, 1.0),
+            SigElement::new(2.0,2.5, 1.0),
+            SigElement::new(2.5,3.0,-1.0),
+            SigElement::new(3.0,3.5, 1.0),
+            SigElement::new(3.5,4.0,-1.0),
+        ]),
+    ]);
+
+    test_len_case!(test_diff_manchester_len6_cases: DiffManchester::new() => [
+        ([0,1,1,1,1,0], [
+            SigElement::new(0.0,0.5, 1.0),
+            SigElement::new(0.5,1.0,-1.0),
+            SigElement::new(1.0,1.5,-1.0),
+            SigElement::new(1.5,2.0, 1.0),
+            SigElement::new(2.0,2.5, 1.0),
+            SigElement::new(2.5,3.0,-1.0),
+            SigElement::new(3.0,3.5,-1.0),
+            SigElement::new(3.5,4.0, 1.0),
+            SigElement::new(4.0,4.5, 1.0),
+            SigElement::new(4.5,5.0,-1.0),
+            SigElement::new(5.0,5.5, 1.0),
+            SigElement::new(5.5,6.0,-1.0),
+        ]),
+        ([1,0,1,0,1,0], [
+            SigElement::new(0.0,0.5,-1.0),
+            SigElement::new(0.5,1.0, 1.0),
+            SigElement::new(1.0,1.5,-1.0),
+            SigElement::new(1.5,2.0, 1.0),
+            SigElement::new(2.0,2.5, 1.0),
+            SigElement::new(2.5,3.0,-1.0),
+            SigElement::new(3.0,3.5, 1.0),
+            SigElement::new(3.5,4.0,-1.0),
+            SigElement::new(4.0,4.5,-1.0),
+            SigElement::new(4.5,5.0, 1.0),
+            SigElement::new(5.0,5.5,-1.0),
+            SigElement::new(5.5,6.0, 1.0),
+        ]),
+    ]);
+
+    test_len_case!(test_diff_manchester_len8_cases: DiffManchester::new() => [
+        ([0,0,1,1,0,0,1,1], [
+            SigElement::new(0.0,0.5, 1.0),
+            SigElement::new(0.5,1.0,-1.0),
+            SigElement::new(1.0,1.5, 1.0),
+            SigElement::new(1.5,2.0,-1.0),
+            SigElement::new(2.0,2.5,-1.0),
+            SigElement::new(2.5,3.0, 1.0),
+            SigElement::new(3.0,3.5, 1.0),
+            SigElement::new(3.5,4.0,-1.0),
+            SigElement::new(4.0,4.5, 1.0),
+            SigElement::new(4.5,5.0,-1.0),
+            SigElement::new(5.0,5.5, 1.0),
+            SigElement::new(5.5,6.0,-1.0),
+            SigElement::new(6.0,6.5,-1.0),
+            SigElement::new(6.5,7.0, 1.0),
+            SigElement::new(7.0,7.5, 1.0),
+            SigElement::new(7.5,8.0,-1.0),
+        ]),
+        ([0,1,0,1,0,1,1,1], [
+            SigElement::new(0.0,0.5, 1.0),
+            SigElement::new(0.5,1.0,-1.0),
+            SigElement::new(1.0,1.5,-1.0),
+            SigElement::new(1.5,2.0, 1.0),
+            SigElement::new(2.0,2.5,-1.0),
+            SigElement::new(2.5,3.0, 1.0),
+            SigElement::new(3.0,3.5, 1.0),
+            SigElement::new(3.5,4.0,-1.0),
+            SigElement::new(4.0,4.5, 1.0),
+            SigElement::new(4.5,5.0,-1.0),
+            SigElement::new(5.0,5.5,-1.0),
+            SigElement::new(5.5,6.0, 1.0),
+            SigElement::new(6.0,6.5, 1.0),
+            SigElement::new(6.5,7.0,-1.0),
+            SigElement::new(7.0,7.5,-1.0),
+            SigElement::new(7.5,8.0, 1.0),
+        ]),
+    ]);
+
+    #[test]
+    fn test_diff_manchester_unarios_len1() {
+        let enc = DiffManchester::new();
+
+        let s0 = [0u8; 1];
+        let e0 = [
+            SigElement::new(0.0, 0.5, 1.0),
+            SigElement::new(0.5, 1.0, -1.0),
+        ];
+        assert_eq!(enc.encode(&s0).as_ref(), &e0);
+
+        let s1 = [1u8; 1];
+        let e1 = [
+            SigElement::new(0.0, 0.5, -1.0),
+            SigElement::new(0.5, 1.0, 1.0),
+        ];
+        assert_eq!(enc.encode(&s1).as_ref(), &e1);
+    }
 }
@@ -1,9 +1,13 @@
-use super::{LineCoder, SigElement};
+use super::{
+    LineCoder, LineDecoder, SigElement, StreamEncoder,
+    stream::{Step, Stepper},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Mlt3 {
     tb: f64,
     v: f64,
+    idx: usize,
 }
 
 impl Default for Mlt3 {
@@ -11,6 +15,7 @@ impl Default for Mlt3 {
         Self {
             tb: super::GLOB_BASE_TB,
             v: super::GLOB_BASE_V,
+            idx: Self::CYCLE_BUMP_IDX,
         }
     }
 }
@@ -22,28 +27,75 @@ impl Mlt3 {
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_state(mut self, idx: usize) -> Self {
+        self.idx = idx & 3;
+        self
+    }
 }
 
 impl LineCoder for Mlt3 {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let cycle = [0.0, self.v, 0.0, -self.v];
-        let mut idx = Self::CYCLE_BUMP_IDX;
-        let mut t = 0.0;
-
-        bits.iter()
-            .filter_map(|&bit| {
-                if bit == 1 {
-                    idx = (idx + 1) & 3;
-                }
-
-                let mut res = None;
-                let tf = t + self.tb;
-                if tf > t {
-                    res = Some(SigElement::new(t, tf, cycle[idx]));
-                }
-
-                t += self.tb;
-                res
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+impl Step for Mlt3 {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        if bit == 1 {
+            self.idx = (self.idx + 1) & 3;
+        }
+
+        let tf = t + self.tb;
+        if tf > t {
+            out.push(SigElement::new(
+                t,
+                tf,
+                [0.0, self.v, 0.0, -self.v][self.idx],
+            ));
+        }
+    }
+}
+
+impl LineDecoder for Mlt3 {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let thr = self.v.abs() / 2.0;
+        let quantize = |lvl: f64| {
+            if lvl > thr {
+                1
+            } else if lvl < -thr {
+                -1
+            } else {
+                0
+            }
+        };
+
+        let mut prev = quantize([0.0, self.v, 0.0, -self.v][self.idx]);
+        super::probe(sigs, self.tb, 0.5)
+            .map(|lvl| {
+                let lvl = quantize(lvl);
+                let bit = (lvl != prev) as u8;
+                prev = lvl;
+                bit
             })
             .collect()
     }
@@ -52,8 +104,23 @@ impl LineCoder for Mlt3 {
 #[cfg(test)]
 mod tests {
     use crate::coder::{LineCoder, SigElement, mlt3::Mlt3};
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    test_round_trip!(test_mlt3_round_trip: Mlt3::new());
+
+    test_round_trip!(test_mlt3_configured_round_trip: Mlt3::new().with_initial_state(3));
+
+    test_stream!(test_mlt3_stream: Mlt3::new().with_initial_state(2));
+
+    test_len_case!(test_mlt3_configured_cases: Mlt3::new().with_initial_state(3) => [
+        ([0,1,1], [
+            SigElement::new(0.0,1.0,-1.0),
+            SigElement::new(1.0,2.0, 0.0),
+            SigElement::new(2.0,3.0, 1.0),
+        ]),
+    ]);
 
-    crate::test_len_case!(test_mlt3_len4_cases: Mlt3::new() => [
+    test_len_case!(test_mlt3_len4_cases: Mlt3::new() => [
         ([0,0,0,0], [
             SigElement::new(0.0,1.0, 0.0),
             SigElement::new(1.0,2.0, 0.0),
@@ -80,7 +147,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_mlt3_len6_cases: Mlt3::new() => [
+    test_len_case!(test_mlt3_len6_cases: Mlt3::new() => [
         ([1,0,1,0,1,0], [
             SigElement::new(0.0,1.0, 1.0),
             SigElement::new(1.0,2.0, 1.0),
@@ -99,7 +166,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_mlt3_len8_cases: Mlt3::new() => [
+    test_len_case!(test_mlt3_len8_cases: Mlt3::new() => [
         ([0,0,1,1,0,0,1,1], [
             SigElement::new(0.0,1.0, 0.0),
             SigElement::new(1.0,2.0, 0.0),
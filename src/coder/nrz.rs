@@ -1,4 +1,7 @@
-use super::{LineCoder, SigElement};
+use super::{
+    LineCoder, LineDecoder, Polarity, SigElement, StreamEncoder,
+    stream::{Step, Stepper},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Nrzl {
@@ -20,23 +23,46 @@ impl Nrzl {
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
 }
 
 impl LineCoder for Nrzl {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let mut t = 0.0;
-        bits.iter()
-            .filter_map(|&bit| {
-                let mut res = None;
-                let tf = t + self.tb;
-                if tf > t {
-                    let lvl = if bit == 1 { self.v } else { -self.v };
-                    res = Some(SigElement::new(t, tf, lvl));
-                }
-
-                t += self.tb;
-                res
-            })
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+impl Step for Nrzl {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        let tf = t + self.tb;
+        if tf > t {
+            let lvl = if bit == 1 { self.v } else { -self.v };
+            out.push(SigElement::new(t, tf, lvl));
+        }
+    }
+}
+
+impl LineDecoder for Nrzl {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        super::probe(sigs, self.tb, 0.5)
+            .map(|lvl| (lvl > 0.0) as u8)
             .collect()
     }
 }
@@ -61,25 +87,59 @@ impl Nrzi {
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_initial_polarity(mut self, pol: Polarity) -> Self {
+        self.v = self.v.abs() * pol.sign();
+        self
+    }
 }
 
 impl LineCoder for Nrzi {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let mut t = 0.0;
-        let mut lvl = self.v;
-        bits.iter()
-            .filter_map(|&bit| {
-                let mut res = None;
-                let tf = t + self.tb;
-                if tf > t {
-                    if bit == 1 {
-                        lvl = -lvl;
-                    }
-                    res = Some(SigElement::new(t, tf, lvl));
-                }
-
-                t += self.tb;
-                res
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+// The amplitude's sign is the level of the previous bit.
+impl Step for Nrzi {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        let tf = t + self.tb;
+        if tf > t {
+            if bit == 1 {
+                self.v = -self.v;
+            }
+            out.push(SigElement::new(t, tf, self.v));
+        }
+    }
+}
+
+impl LineDecoder for Nrzi {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        let mut prev = self.v;
+        super::probe(sigs, self.tb, 0.5)
+            .map(|lvl| {
+                let bit = ((lvl > 0.0) != (prev > 0.0)) as u8;
+                prev = lvl;
+                bit
             })
             .collect()
     }
@@ -88,9 +148,35 @@ impl LineCoder for Nrzi {
 #[cfg(test)]
 mod tests {
     use super::{Nrzi, Nrzl};
-    use crate::coder::{LineCoder, SigElement};
+    use crate::coder::{LineCoder, Polarity, SigElement};
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    test_round_trip!(test_nrzl_round_trip: Nrzl::new());
+
+    test_round_trip!(test_nrzi_round_trip: Nrzi::new());
+
+    test_round_trip!(test_nrzi_configured_round_trip: Nrzi::new()
+        .with_bit_rate(8.0)
+        .with_initial_polarity(Polarity::Negative));
+
+    test_stream!(test_nrzl_stream: Nrzl::new());
+
+    test_stream!(test_nrzi_stream: Nrzi::new().with_initial_polarity(Polarity::Negative));
+
+    test_len_case!(test_nrzi_configured_cases: Nrzi::new()
+        .with_bit_rate(2.0)
+        .with_amplitude(3.0)
+        .with_initial_polarity(Polarity::Negative) => [
+        ([1, 0, 1],
+            [
+                SigElement::new(0.0, 0.5, 3.0),
+                SigElement::new(0.5, 1.0, 3.0),
+                SigElement::new(1.0, 1.5, -3.0),
+            ]
+        ),
+    ]);
 
-    crate::test_len_case!(test_nrzl_len4_cases: Nrzl::new() => [
+    test_len_case!(test_nrzl_len4_cases: Nrzl::new() => [
         ([0, 0, 0, 0],
             [
                 SigElement::new(0.0, 1.0, -1.0),
@@ -125,7 +211,7 @@ mod tests {
         ),
     ]);
 
-    crate::test_len_case!(test_nrzi_len4_cases: Nrzi::new() => [
+    test_len_case!(test_nrzi_len4_cases: Nrzi::new() => [
         ([0, 0, 0, 0],
             [
                 SigElement::new(0.0, 1.0, 1.0),
@@ -160,7 +246,7 @@ mod tests {
         ),
     ]);
 
-    crate::test_len_case!(test_nrzl_len6_cases: Nrzl::new() => [
+    test_len_case!(test_nrzl_len6_cases: Nrzl::new() => [
         ([1, 0, 1, 0, 1, 0],
             [
                 SigElement::new(0.0, 1.0, 1.0),
@@ -183,7 +269,7 @@ mod tests {
         ),
     ]);
 
-    crate::test_len_case!(test_nrzi_len6_cases: Nrzi::new() => [
+    test_len_case!(test_nrzi_len6_cases: Nrzi::new() => [
         ([1, 0, 1, 0, 1, 0],
             [
                 SigElement::new(0.0, 1.0, -1.0),
@@ -206,7 +292,7 @@ mod tests {
         ),
     ]);
 
-    crate::test_len_case!(test_nrzl_len8_cases: Nrzl::new() => [
+    test_len_case!(test_nrzl_len8_cases: Nrzl::new() => [
         ([0, 0, 1, 1, 0, 0, 1, 1],
             [
                 SigElement::new(0.0, 1.0, -1.0),
@@ -233,7 +319,7 @@ mod tests {
         ),
     ]);
 
-    crate::test_len_case!(test_nrzi_len8_cases: Nrzi::new() => [
+    test_len_case!(test_nrzi_len8_cases: Nrzi::new() => [
         ([0, 0, 1, 1, 0, 0, 1, 1],
             [
                 SigElement::new(0.0, 1.0, 1.0),
@@ -0,0 +1,398 @@
+use std::{fmt::Debug, sync::Arc};
+
+use super::{
+    LineCoder, Polarity,
+    ami::Ami,
+    b8zs::B8zs,
+    catalog::Settings,
+    hdb3::Hdb3,
+    manch::{DiffManchester, Manchester},
+    mlt3::Mlt3,
+    nrz::{Nrzi, Nrzl},
+    rz::Rz,
+};
+
+pub type Constructor = Arc<dyn Fn(&Settings) -> Box<dyn LineCoder> + Send + Sync>;
+
+// Bit rate and amplitude apply to every coder, so the schema only lists the
+// settings a coder may or may not take.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    init_states: Box<[String]>,
+    def_init_state: usize,
+    duty: bool,
+}
+
+impl Schema {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_init_states<S: Into<String>>(
+        mut self,
+        states: impl IntoIterator<Item = S>,
+        def: usize,
+    ) -> Self {
+        self.init_states = states.into_iter().map(Into::into).collect();
+        self.def_init_state = def.min(self.init_states.len().saturating_sub(1));
+        self
+    }
+
+    #[inline]
+    pub fn with_duty(mut self) -> Self {
+        self.duty = true;
+        self
+    }
+
+    #[inline]
+    pub fn init_states(&self) -> &[String] {
+        &self.init_states
+    }
+
+    #[inline]
+    pub fn def_init_state(&self) -> usize {
+        self.def_init_state
+    }
+
+    #[inline]
+    pub fn has_duty(&self) -> bool {
+        self.duty
+    }
+}
+
+#[derive(Clone)]
+pub struct Entry {
+    id: String,
+    name: String,
+    description: String,
+    schema: Schema,
+    build: Constructor,
+}
+
+impl Entry {
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        build: impl Fn(&Settings) -> Box<dyn LineCoder> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            description: String::new(),
+            schema: Schema::new(),
+            build: Arc::new(build),
+        }
+    }
+
+    #[inline]
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    #[inline]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    #[inline]
+    pub fn build(&self, settings: &Settings) -> Box<dyn LineCoder> {
+        (self.build)(settings)
+    }
+}
+
+impl Debug for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Entry")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("schema", &self.schema)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    const POLARITY_STATES: [&str; 2] = ["+V", "-V"];
+    // AMI-like coders alternate from the polarity of the mark before the
+    // first one.
+    const MARK_STATES: [&str; 2] = ["last mark +V", "last mark -V"];
+    const MLT3_STATES: [&str; 4] = ["0 (rising)", "+V", "0 (falling)", "-V"];
+
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn builtin() -> Self {
+        let polarity = |def| Schema::new().with_init_states(Self::POLARITY_STATES, def);
+        let marks = |def| Schema::new().with_init_states(Self::MARK_STATES, def);
+        let pol = |settings: &Settings| {
+            if settings.init_state == 0 {
+                Polarity::Positive
+            } else {
+                Polarity::Negative
+            }
+        };
+
+        let mut registry = Self::new();
+        registry.register(
+            Entry::new("nrzl", "NRZ-L", |s| {
+                Nrzl::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .boxed()
+            })
+            .with_description(
+                "Non-return-to-zero level: +V for a 1 and -V for a 0 over the whole bit.",
+            ),
+        );
+        registry.register(
+            Entry::new("nrzi", "NRZ-I", move |s| {
+                Nrzi::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_initial_polarity(pol(s))
+                    .boxed()
+            })
+            .with_description(
+                "Non-return-to-zero inverted: a 1 toggles the level at the start of the bit.",
+            )
+            .with_schema(polarity(0)),
+        );
+        registry.register(
+            Entry::new("rz", "RZ", |s| {
+                Rz::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_duty(s.duty)
+                    .boxed()
+            })
+            .with_description(
+                "Return-to-zero: a pulse of the bit's polarity, then 0 V for the rest of the bit.",
+            )
+            .with_schema(Schema::new().with_duty()),
+        );
+        registry.register(
+            Entry::new("manchester", "Manchester 802.3", |s| {
+                Manchester::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_duty(s.duty)
+                    .boxed()
+            })
+            .with_description(
+                "IEEE 802.3 Manchester: a 1 rises and a 0 falls in the middle of the bit.",
+            )
+            .with_schema(Schema::new().with_duty()),
+        );
+        registry.register(
+            Entry::new("diff-manchester", "Diff. Manchester 802.5", move |s| {
+                DiffManchester::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_initial_polarity(pol(s))
+                    .with_duty(s.duty)
+                    .boxed()
+            })
+            .with_description(
+                "IEEE 802.5 differential Manchester: a transition mid-bit, plus one at the start of a 0.",
+            )
+            .with_schema(polarity(1).with_duty()),
+        );
+        registry.register(
+            Entry::new("hdb3", "HDB3", move |s| {
+                Hdb3::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_initial_polarity(pol(s))
+                    .boxed()
+            })
+            .with_description(
+                "AMI with every run of four zeros replaced by 000V or B00V, as on E1 lines.",
+            )
+            .with_schema(marks(1)),
+        );
+        registry.register(
+            Entry::new("b8zs", "B8ZS", move |s| {
+                B8zs::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_initial_polarity(pol(s))
+                    .boxed()
+            })
+            .with_description(
+                "AMI with every run of eight zeros replaced by 000VB0VB, as on T1 lines.",
+            )
+            .with_schema(marks(1)),
+        );
+        registry.register(
+            Entry::new("mlt3", "MLT-3", |s| {
+                Mlt3::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_initial_state(s.init_state)
+                    .boxed()
+            })
+            .with_description(
+                "Multi-level transmit: a 1 steps through 0, +V, 0, -V and a 0 holds the level.",
+            )
+            .with_schema(Schema::new().with_init_states(Self::MLT3_STATES, 0)),
+        );
+        registry.register(
+            Entry::new("ami", "AMI", move |s| {
+                Ami::new()
+                    .with_bit_rate(s.bit_rate)
+                    .with_amplitude(s.amplitude)
+                    .with_initial_polarity(pol(s))
+                    .boxed()
+            })
+            .with_description(
+                "Alternate mark inversion: 0 V for a 0 and alternating +V and -V for each 1.",
+            )
+            .with_schema(marks(1)),
+        );
+
+        registry
+    }
+
+    // Ids are matched case-insensitively, and an entry whose id is taken
+    // replaces the previous one in place, which is returned.
+    pub fn register(&mut self, entry: Entry) -> Option<Entry> {
+        match self.position(entry.id()) {
+            Some(idx) => Some(std::mem::replace(&mut self.entries[idx], entry)),
+            None => {
+                self.entries.push(entry);
+                None
+            }
+        }
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    #[inline]
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.id().eq_ignore_ascii_case(id))
+    }
+
+    #[inline]
+    pub fn get(&self, id: &str) -> Option<&Entry> {
+        self.position(id).map(|idx| &self.entries[idx])
+    }
+
+    pub fn ids(&self) -> String {
+        self.entries
+            .iter()
+            .map(Entry::id)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Registry, Schema};
+    use crate::coder::{LineCoder, catalog::Settings, nrz::Nrzl};
+
+    #[test]
+    fn test_registry_ids_resolve_back_to_entries() {
+        let registry = Registry::builtin();
+        for entry in registry.entries() {
+            assert_eq!(
+                registry.get(entry.id()).map(Entry::name),
+                Some(entry.name())
+            );
+            assert!(!entry.description().is_empty());
+        }
+        assert_eq!(registry.get("HDB3").map(Entry::name), Some("HDB3"));
+        assert!(registry.get("hdb2").is_none());
+        assert_eq!(registry.ids().split(", ").count(), registry.entries().len());
+    }
+
+    #[test]
+    fn test_registry_builds_with_schema_defaults() {
+        let registry = Registry::builtin();
+        let mlt3 = registry.get("mlt3").unwrap().schema();
+        assert_eq!(mlt3.init_states().len(), 4);
+        assert_eq!(
+            registry.get("ami").unwrap().schema().init_states(),
+            ["last mark +V", "last mark -V"]
+        );
+        assert!(!mlt3.has_duty());
+        assert!(registry.get("rz").unwrap().schema().has_duty());
+        assert!(
+            registry
+                .get("nrzl")
+                .unwrap()
+                .schema()
+                .init_states()
+                .is_empty()
+        );
+
+        let settings = Settings {
+            bit_rate: 4.0,
+            ..Default::default()
+        };
+        for entry in registry.entries() {
+            assert_eq!(entry.build(&settings).bit_time(), 0.25, "{}", entry.id());
+        }
+    }
+
+    #[test]
+    fn test_registry_accepts_third_party_entries() {
+        let mut registry = Registry::builtin();
+        let len = registry.entries().len();
+
+        let custom = Entry::new("slow-nrz", "Slow NRZ", |s: &Settings| {
+            Nrzl::new().with_bit_rate(s.bit_rate / 2.0).boxed()
+        })
+        .with_schema(Schema::new().with_init_states(["a", "b"], 5));
+        assert!(registry.register(custom).is_none());
+        assert_eq!(registry.entries().len(), len + 1);
+        assert_eq!(
+            registry.get("slow-nrz").unwrap().schema().def_init_state(),
+            1
+        );
+
+        let replaced = registry.register(Entry::new("NRZL", "Mine", |_: &Settings| {
+            Nrzl::new().boxed()
+        }));
+        assert_eq!(
+            replaced.map(|entry| entry.name().to_owned()).as_deref(),
+            Some("NRZ-L")
+        );
+        assert_eq!(registry.entries().len(), len + 1);
+        assert_eq!(registry.entries()[0].name(), "Mine");
+    }
+}
@@ -1,9 +1,13 @@
-use super::{LineCoder, SigElement};
+use super::{
+    LineCoder, LineDecoder, SigElement, StreamEncoder,
+    stream::{Step, Stepper},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rz {
     tb: f64,
     v: f64,
+    duty: f64,
 }
 
 impl Default for Rz {
@@ -11,52 +15,95 @@ impl Default for Rz {
         Self {
             tb: super::GLOB_BASE_TB,
             v: super::GLOB_BASE_V,
+            duty: super::GLOB_BASE_DUTY,
         }
     }
 }
 
 impl Rz {
-    const DEF_DUTY: f64 = 0.5;
-
     #[inline]
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.tb = super::bit_time_for(rate, self.tb);
+        self
+    }
+
+    #[inline]
+    pub fn with_amplitude(mut self, v: f64) -> Self {
+        self.v = super::level_for(v, self.v);
+        self
+    }
+
+    #[inline]
+    pub fn with_duty(mut self, duty: f64) -> Self {
+        self.duty = super::duty_for(duty, self.duty);
+        self
+    }
 }
 
 impl LineCoder for Rz {
-    fn encode(&self, bits: &[u8]) -> Box<[SigElement]> {
-        let h = self.tb * Self::DEF_DUTY;
-        let mut t = 0.0;
-        let mut out = Vec::new();
-
-        for &bit in bits {
-            let tf0 = t + h;
-            if tf0 > t {
-                out.push(SigElement::new(
-                    t,
-                    tf0,
-                    if bit == 1 { self.v } else { -self.v },
-                ));
-            }
-
-            let tf1 = t + self.tb;
-            if tf1 > tf0 {
-                out.push(SigElement::new(tf0, tf1, 0.0));
-            }
-
-            t += self.tb;
+    #[inline]
+    fn stream(&self) -> Box<dyn StreamEncoder> {
+        Stepper::boxed(*self)
+    }
+
+    #[inline]
+    fn bit_time(&self) -> f64 {
+        self.tb
+    }
+}
+
+impl Step for Rz {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>) {
+        let tf0 = t + self.tb * self.duty;
+        if tf0 > t {
+            out.push(SigElement::new(
+                t,
+                tf0,
+                if bit == 1 { self.v } else { -self.v },
+            ));
         }
 
-        out.into_boxed_slice()
+        let tf1 = t + self.tb;
+        if tf1 > tf0 {
+            out.push(SigElement::new(tf0, tf1, 0.0));
+        }
+    }
+}
+
+impl LineDecoder for Rz {
+    fn decode(&self, sigs: &[SigElement]) -> Box<[u8]> {
+        super::probe(sigs, self.tb, self.duty / 2.0)
+            .map(|lvl| (lvl > 0.0) as u8)
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::coder::{LineCoder, SigElement, rz::Rz};
+    use crate::util::testing::{test_len_case, test_round_trip, test_stream};
+
+    test_round_trip!(test_rz_round_trip: Rz::new());
+
+    test_round_trip!(test_rz_configured_round_trip: Rz::new().with_bit_rate(4.0).with_duty(0.3));
+
+    test_stream!(test_rz_stream: Rz::new().with_duty(0.3));
+
+    test_len_case!(test_rz_configured_cases: Rz::new().with_bit_rate(2.0).with_duty(0.25) => [
+        ([1,0], [
+            SigElement::new(0.0,0.125,1.0),
+            SigElement::new(0.125,0.5,0.0),
+            SigElement::new(0.5,0.625,-1.0),
+            SigElement::new(0.625,1.0,0.0),
+        ]),
+    ]);
 
-    crate::test_len_case!(test_rz_len4_cases: Rz::new() => [
+    test_len_case!(test_rz_len4_cases: Rz::new() => [
         ([0,0,0,0], [
             SigElement::new(0.0,0.5,-1.0),
             SigElement::new(0.5,1.0,0.0),
@@ -89,7 +136,7 @@ mod tests {
         ]),
     ]);
 
-    crate::test_len_case!(test_rz_len6_cases: Rz::new() => [
+    test_len_case!(test_rz_len6_cases: Rz::new() => [
         ([0,1,1,1,1,0], [
             SigElement::new(0.0,0.5,-1.0),
             SigElement::new(0.5,1.0,0.0),
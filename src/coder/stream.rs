@@ -0,0 +1,107 @@
+use super::{LineCoder, SigElement, StreamEncoder};
+
+// Coders whose output for a bit depends only on the bits before it keep their
+// running state in their own fields, so a copy of the coder is its stream.
+pub(super) trait Step: LineCoder + Clone {
+    fn step(&mut self, bit: u8, t: f64, out: &mut Vec<SigElement>);
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Stepper<C> {
+    coder: C,
+    t: f64,
+}
+
+impl<C: Step> Stepper<C> {
+    #[inline]
+    pub(super) fn boxed(coder: C) -> Box<dyn StreamEncoder> {
+        Box::new(Self { coder, t: 0.0 })
+    }
+}
+
+impl<C: Step> StreamEncoder for Stepper<C> {
+    fn push(&mut self, bits: &[u8], out: &mut Vec<SigElement>) {
+        let tb = self.coder.bit_time();
+        for &bit in bits {
+            self.coder.step(bit, self.t, out);
+            self.t += tb;
+        }
+    }
+
+    #[inline]
+    fn boxed_clone(&self) -> Box<dyn StreamEncoder> {
+        Box::new(self.clone())
+    }
+}
+
+// Encodes a sequence that keeps being edited, pushing only the bits appended
+// since the last call and starting over when an earlier bit changed.
+#[derive(Clone)]
+pub struct Incremental {
+    start: Box<dyn StreamEncoder>,
+    enc: Box<dyn StreamEncoder>,
+    bits: Vec<u8>,
+    sigs: Vec<SigElement>,
+}
+
+impl Incremental {
+    pub fn new(coder: &dyn LineCoder) -> Self {
+        let start = coder.stream();
+        Self {
+            enc: start.clone(),
+            start,
+            bits: Vec::new(),
+            sigs: Vec::new(),
+        }
+    }
+
+    pub fn encode(&mut self, bits: &[u8]) -> Box<[SigElement]> {
+        if !bits.starts_with(&self.bits) {
+            self.enc = self.start.clone();
+            self.bits.clear();
+            self.sigs.clear();
+        }
+
+        let new = &bits[self.bits.len()..];
+        self.enc.push(new, &mut self.sigs);
+        self.bits.extend_from_slice(new);
+
+        let mut out = self.sigs.clone();
+        self.enc.clone().finish(&mut out);
+        out.into_boxed_slice()
+    }
+
+    #[inline]
+    pub fn bits(&self) -> &[u8] {
+        &self.bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Incremental;
+    use crate::coder::{LineCoder, hdb3::Hdb3, mlt3::Mlt3};
+
+    #[test]
+    fn test_incremental_matches_encode_while_typing() {
+        let seq = [1, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0];
+        for coder in [Hdb3::new().boxed(), Mlt3::new().boxed()] {
+            let mut inc = Incremental::new(coder.as_ref());
+            for len in 0..=seq.len() {
+                assert_eq!(inc.encode(&seq[..len]), coder.encode(&seq[..len]), "{len}");
+                assert_eq!(inc.bits(), &seq[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_restarts_after_edits() {
+        let coder = Hdb3::new();
+        let mut inc = Incremental::new(&coder);
+
+        inc.encode(&[1, 0, 0, 0, 0, 1]);
+        assert_eq!(inc.encode(&[1, 0, 1]), coder.encode(&[1, 0, 1]));
+        assert_eq!(inc.encode(&[0, 1]), coder.encode(&[0, 1]));
+        assert_eq!(inc.encode(&[]), coder.encode(&[]));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod svg;
+pub mod vcd;
+pub mod wav;
@@ -0,0 +1,259 @@
+use std::fmt::Write as _;
+
+use crate::{analysis, coder::SigElement, util};
+
+#[derive(Debug, Clone)]
+pub struct Svg {
+    bit_time: f64,
+    title: String,
+    window: Option<(f64, f64)>,
+}
+
+impl Svg {
+    const WIDTH: f64 = 960.0;
+    const HEIGHT: f64 = 360.0;
+    const MARGIN_LEFT: f64 = 70.0;
+    const MARGIN_RIGHT: f64 = 20.0;
+    const MARGIN_TOP: f64 = 60.0;
+    const MARGIN_BOTTOM: f64 = 50.0;
+    const X_TICKS: usize = 5;
+    const FONT: &str = "font-family=\"monospace\" font-size=\"12\"";
+
+    #[inline]
+    pub fn new(bit_time: f64) -> Self {
+        Self {
+            bit_time,
+            title: String::new(),
+            window: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_window(mut self, t0: f64, t1: f64) -> Self {
+        self.window = Some((t0, t1));
+        self
+    }
+
+    // Bit cells split the waveform evenly among `bits`, as the VCD data track
+    // does, so block coded input still lines up with its line bits.
+    pub fn render(&self, sigs: &[SigElement], bits: Option<&[u8]>) -> String {
+        let end = sigs.last().map(|s| s.tf()).unwrap_or_default();
+        let (t0, t1) = self
+            .window
+            .filter(|(t0, t1)| t1 > t0)
+            .unwrap_or((0.0, if end > 0.0 { end } else { self.bit_time }));
+        let bits = bits.filter(|bits| !bits.is_empty());
+        let cell = bits.map_or(self.bit_time, |bits| end / bits.len() as f64);
+
+        let mut levels = sigs.iter().map(|s| s.lvl()).collect::<Vec<_>>();
+        levels.push(0.0);
+        levels.sort_by(f64::total_cmp);
+        levels.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        let lo = levels.first().copied().unwrap_or_default();
+        let hi = levels.last().copied().unwrap_or_default();
+        let (y0, y1) = if lo < 0.0 {
+            let m = hi.max(-lo).max(1e-9) * 1.15;
+            (-m, m)
+        } else {
+            (0.0, hi.max(1e-9) * 1.15)
+        };
+
+        let (left, top) = (Self::MARGIN_LEFT, Self::MARGIN_TOP);
+        let right = Self::WIDTH - Self::MARGIN_RIGHT;
+        let bottom = Self::HEIGHT - Self::MARGIN_BOTTOM;
+        let px = |t: f64| left + (t - t0) / (t1 - t0) * (right - left);
+        let py = |v: f64| bottom - (v - y0) / (y1 - y0) * (bottom - top);
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = Self::WIDTH,
+            h = Self::HEIGHT,
+        );
+        out.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+        let _ = writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"24\" text-anchor=\"middle\" font-family=\"sans-serif\" font-size=\"18\" font-weight=\"bold\">{}</text>",
+            Self::WIDTH / 2.0,
+            escape(&self.title)
+        );
+        let _ = writeln!(
+            out,
+            "<text x=\"{right:.1}\" y=\"24\" text-anchor=\"end\" {}>{}</text>",
+            Self::FONT,
+            util::fmt_si(self.bit_time.recip(), "bps")
+        );
+
+        out.push_str("<g stroke=\"#bbbbbb\" stroke-dasharray=\"4 4\">\n");
+        let first = (t0 / cell).ceil() as usize;
+        let last = (t1 / cell + 1e-9).floor() as usize;
+        for k in first..=last {
+            let x = px(k as f64 * cell);
+            let _ = writeln!(
+                out,
+                "<line x1=\"{x:.1}\" y1=\"{top:.1}\" x2=\"{x:.1}\" y2=\"{bottom:.1}\"/>"
+            );
+        }
+        out.push_str("</g>\n");
+
+        if let Some(bits) = bits {
+            let _ = writeln!(out, "<g text-anchor=\"middle\" {}>", Self::FONT);
+            for (k, bit) in bits.iter().enumerate() {
+                let mid = (k as f64 + 0.5) * cell;
+                if (t0..=t1).contains(&mid) {
+                    let _ = writeln!(
+                        out,
+                        "<text x=\"{:.1}\" y=\"{:.1}\">{bit}</text>",
+                        px(mid),
+                        top - 8.0
+                    );
+                }
+            }
+            out.push_str("</g>\n");
+        }
+
+        if y0 < 0.0 {
+            let _ = writeln!(
+                out,
+                "<line x1=\"{left:.1}\" y1=\"{y:.1}\" x2=\"{right:.1}\" y2=\"{y:.1}\" stroke=\"#888888\" stroke-dasharray=\"2 3\"/>",
+                y = py(0.0)
+            );
+        }
+
+        let (scale, prefix) = util::si_prefix(t1.abs().max(t0.abs()));
+        let _ = writeln!(
+            out,
+            "<g stroke=\"#333333\">\n<line x1=\"{left:.1}\" y1=\"{bottom:.1}\" x2=\"{right:.1}\" y2=\"{bottom:.1}\"/>\n<line x1=\"{left:.1}\" y1=\"{top:.1}\" x2=\"{left:.1}\" y2=\"{bottom:.1}\"/>\n</g>"
+        );
+        let _ = writeln!(out, "<g fill=\"#333333\" {}>", Self::FONT);
+        for i in 0..Self::X_TICKS {
+            let t = t0 + (t1 - t0) * i as f64 / (Self::X_TICKS - 1) as f64;
+            let x = px(t);
+            let _ = writeln!(
+                out,
+                "<line x1=\"{x:.1}\" y1=\"{bottom:.1}\" x2=\"{x:.1}\" y2=\"{:.1}\" stroke=\"#333333\"/>\n<text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{:.2}</text>",
+                bottom + 5.0,
+                bottom + 18.0,
+                t / scale
+            );
+        }
+        for &lvl in &levels {
+            let y = py(lvl);
+            let _ = writeln!(
+                out,
+                "<line x1=\"{:.1}\" y1=\"{y:.1}\" x2=\"{left:.1}\" y2=\"{y:.1}\" stroke=\"#333333\"/>\n<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+                left - 5.0,
+                left - 8.0,
+                y + 4.0,
+                util::fmt_si(lvl, "V")
+            );
+        }
+        let _ = writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">Time ({prefix}s)</text>\n</g>",
+            (left + right) / 2.0,
+            Self::HEIGHT - 12.0
+        );
+
+        let points = clip(&analysis::polyline(sigs), sigs, t0, t1);
+        if !points.is_empty() {
+            out.push_str("<polyline fill=\"none\" stroke=\"#1f77b4\" stroke-width=\"2\" points=\"");
+            for (i, &(t, v)) in points.iter().enumerate() {
+                let sep = if i == 0 { "" } else { " " };
+                let _ = write!(out, "{sep}{:.1},{:.1}", px(t), py(v));
+            }
+            out.push_str("\"/>\n");
+        }
+        out.push_str("</svg>\n");
+
+        out
+    }
+}
+
+fn clip(points: &[(f64, f64)], sigs: &[SigElement], t0: f64, t1: f64) -> Vec<(f64, f64)> {
+    let (Some(first), Some(last)) = (sigs.first(), sigs.last()) else {
+        return Vec::new();
+    };
+    let (t0, t1) = (t0.max(first.ti()), t1.min(last.tf()));
+    if t1 <= t0 {
+        return Vec::new();
+    }
+
+    let after = sigs.partition_point(|s| s.tf() <= t0);
+    let before = sigs.partition_point(|s| s.tf() < t1);
+    let mut clipped = vec![(t0, sigs[after.min(sigs.len() - 1)].lvl())];
+    clipped.extend(points.iter().filter(|p| p.0 > t0 && p.0 < t1));
+    clipped.push((t1, sigs[before.min(sigs.len() - 1)].lvl()));
+
+    clipped
+}
+
+fn escape(src: &str) -> String {
+    src.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Svg;
+    use crate::coder::{LineCoder, ami::Ami, nrz::Nrzl};
+
+    fn grid_lines(svg: &str) -> usize {
+        svg.split("stroke-dasharray=\"4 4\">")
+            .nth(1)
+            .and_then(|grid| grid.split("</g>").next())
+            .map_or(0, |grid| grid.matches("<line").count())
+    }
+
+    fn polyline(svg: &str) -> Vec<&str> {
+        svg.split("points=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .map_or_else(Vec::new, |points| points.split(' ').collect())
+    }
+
+    #[test]
+    fn test_svg_draws_waveform_grid_and_labels() {
+        let coder = Ami::new();
+        let bits = [1, 0, 1, 1];
+        let svg = Svg::new(coder.bit_time())
+            .with_title("AMI <test>")
+            .render(&coder.encode(&bits), Some(&bits));
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("AMI &lt;test&gt;"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(grid_lines(&svg), 5);
+        assert_eq!(svg.matches(">1</text>").count(), 3);
+        assert_eq!(svg.matches(">0</text>").count(), 1);
+        for label in [">-1 V<", ">0 V<", ">1 V<"] {
+            assert!(svg.contains(label), "missing level label {label}");
+        }
+    }
+
+    #[test]
+    fn test_svg_window_clips_waveform() {
+        let coder = Nrzl::new();
+        let bits = [1, 0, 1, 0, 1, 0];
+        let svg = Svg::new(coder.bit_time())
+            .with_window(1.5, 3.5)
+            .render(&coder.encode(&bits), Some(&bits));
+
+        let points = polyline(&svg);
+        assert!(points.first().unwrap().starts_with("70.0,"));
+        assert!(points.last().unwrap().starts_with("940.0,"));
+        assert_eq!(points.len(), 2 + 4);
+        assert_eq!(grid_lines(&svg), 2);
+        assert_eq!(svg.matches(">0</text>").count(), 2);
+        assert_eq!(svg.matches(">1</text>").count(), 1);
+    }
+}
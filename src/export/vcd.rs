@@ -0,0 +1,250 @@
+use std::{fmt, fmt::Write as _, str::FromStr};
+
+use crate::coder::SigElement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timescale {
+    mult: u32,
+    exp: i32,
+}
+
+impl Timescale {
+    const UNITS: [(i32, &str); 6] = [
+        (0, "s"),
+        (-3, "ms"),
+        (-6, "us"),
+        (-9, "ns"),
+        (-12, "ps"),
+        (-15, "fs"),
+    ];
+    // Ticks per bit the derived timescale keeps at least, so duty cycles and
+    // half bit edges land on whole ticks.
+    const MIN_TICKS_PER_BIT: f64 = 1000.0;
+
+    pub fn for_bit_time(tb: f64) -> Self {
+        let exp = Self::UNITS
+            .iter()
+            .map(|&(exp, _)| exp)
+            .find(|&exp| tb / 10f64.powi(exp) >= Self::MIN_TICKS_PER_BIT)
+            .unwrap_or(-15);
+
+        Self { mult: 1, exp }
+    }
+
+    #[inline]
+    pub fn seconds(&self) -> f64 {
+        self.mult as f64 / 10f64.powi(-self.exp)
+    }
+}
+
+impl fmt::Display for Timescale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = Self::UNITS
+            .iter()
+            .find(|&&(exp, _)| exp == self.exp)
+            .map(|&(_, unit)| unit)
+            .unwrap_or("s");
+        write!(f, "{} {unit}", self.mult)
+    }
+}
+
+impl FromStr for Timescale {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.trim();
+        let split = src
+            .find(|ch: char| !ch.is_ascii_digit())
+            .ok_or_else(|| format!("missing unit in timescale `{src}`"))?;
+        let (mult, unit) = src.split_at(split);
+        let mult = match mult {
+            "1" => 1,
+            "10" => 10,
+            "100" => 100,
+            _ => return Err(format!("timescale must be 1, 10 or 100 units, got `{src}`")),
+        };
+        let exp = Self::UNITS
+            .iter()
+            .find(|&&(_, name)| name == unit.trim())
+            .map(|&(exp, _)| exp)
+            .ok_or_else(|| format!("unknown timescale unit `{}`", unit.trim()))?;
+
+        Ok(Self { mult, exp })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Vcd {
+    bit_time: f64,
+    timescale: Timescale,
+    clock: bool,
+}
+
+impl Vcd {
+    const SIGNAL_ID: char = '!';
+    const CLOCK_ID: char = '"';
+    const DATA_ID: char = '#';
+
+    #[inline]
+    pub fn new(bit_time: f64) -> Self {
+        Self {
+            bit_time,
+            timescale: Timescale::for_bit_time(bit_time),
+            clock: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_timescale(mut self, timescale: Timescale) -> Self {
+        self.timescale = timescale;
+        self
+    }
+
+    #[inline]
+    pub fn with_clock(mut self, clock: bool) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // `data` is spread evenly over the waveform, so block coded input keeps
+    // lining up with the line bits that carry it.
+    pub fn render(&self, sigs: &[SigElement], data: Option<&[u8]>) -> String {
+        let end = sigs.last().map(|s| s.tf()).unwrap_or_default();
+        let ticks = |t: f64| (t / self.timescale.seconds()).round() as u64;
+
+        let mut changes = Vec::new();
+        let mut prev = None;
+        for sig in sigs {
+            if prev != Some(sig.lvl()) {
+                changes.push((ticks(sig.ti()), Self::SIGNAL_ID, format!("r{}", sig.lvl())));
+                prev = Some(sig.lvl());
+            }
+        }
+
+        if self.clock {
+            let n = (end / self.bit_time).round() as usize;
+            for k in 0..n {
+                let t = k as f64 * self.bit_time;
+                changes.push((ticks(t), Self::CLOCK_ID, "1".to_owned()));
+                changes.push((
+                    ticks(t + self.bit_time / 2.0),
+                    Self::CLOCK_ID,
+                    "0".to_owned(),
+                ));
+            }
+        }
+
+        if let Some(data) = data.filter(|data| !data.is_empty()) {
+            let tb = end / data.len() as f64;
+            let mut prev = None;
+            for (i, &bit) in data.iter().enumerate() {
+                if prev != Some(bit) {
+                    changes.push((ticks(i as f64 * tb), Self::DATA_ID, bit.to_string()));
+                    prev = Some(bit);
+                }
+            }
+        }
+        changes.sort_by_key(|&(t, ..)| t);
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "$version {} {} $end",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+        let _ = writeln!(out, "$timescale {} $end", self.timescale);
+        let _ = writeln!(out, "$scope module {} $end", env!("CARGO_PKG_NAME"));
+        let _ = writeln!(out, "$var real 64 {} signal $end", Self::SIGNAL_ID);
+        if self.clock {
+            let _ = writeln!(out, "$var wire 1 {} clock $end", Self::CLOCK_ID);
+        }
+        if data.is_some() {
+            let _ = writeln!(out, "$var wire 1 {} data $end", Self::DATA_ID);
+        }
+        out.push_str("$upscope $end\n$enddefinitions $end\n");
+
+        let mut last = None;
+        for (t, id, val) in changes {
+            if last != Some(t) {
+                let _ = writeln!(out, "#{t}");
+                last = Some(t);
+            }
+            let sep = if val.starts_with('r') { " " } else { "" };
+            let _ = writeln!(out, "{val}{sep}{id}");
+        }
+        if last.is_none_or(|t| t < ticks(end)) {
+            let _ = writeln!(out, "#{}", ticks(end));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Timescale, Vcd};
+    use crate::coder::{LineCoder, manch::Manchester, nrz::Nrzl};
+
+    #[test]
+    fn test_timescale_from_bit_time() {
+        assert_eq!(Timescale::for_bit_time(1.0).to_string(), "1 ms");
+        assert_eq!(Timescale::for_bit_time(1e-3).to_string(), "1 us");
+        assert_eq!(Timescale::for_bit_time(1e-9).to_string(), "1 ps");
+        assert_eq!("10 ns".parse::<Timescale>().unwrap().to_string(), "10 ns");
+        assert_eq!("100us".parse::<Timescale>().unwrap().seconds(), 100e-6);
+        assert!("5 ns".parse::<Timescale>().is_err());
+        assert!("1 hz".parse::<Timescale>().is_err());
+    }
+
+    #[test]
+    fn test_vcd_nrzl_with_tracks() {
+        let enc = Nrzl::new();
+        let bits = [1, 1, 0];
+        let out = Vcd::new(enc.bit_time())
+            .with_clock(true)
+            .render(&enc.encode(&bits), Some(&bits));
+
+        assert_eq!(
+            out,
+            "$version lcv 0.1.0 $end
+$timescale 1 ms $end
+$scope module lcv $end
+$var real 64 ! signal $end
+$var wire 1 \" clock $end
+$var wire 1 # data $end
+$upscope $end
+$enddefinitions $end
+#0
+r1 !
+1\"
+1#
+#500
+0\"
+#1000
+1\"
+#1500
+0\"
+#2000
+r-1 !
+1\"
+0#
+#2500
+0\"
+#3000
+"
+        );
+    }
+
+    #[test]
+    fn test_vcd_custom_timescale() {
+        let enc = Manchester::new().with_bit_rate(1e6);
+        let out = Vcd::new(enc.bit_time())
+            .with_timescale("1 ns".parse().unwrap())
+            .render(&enc.encode(&[0]), None);
+
+        assert!(out.contains("$timescale 1 ns $end"));
+        assert!(out.ends_with("#0\nr1 !\n#500\nr-1 !\n#1000\n"));
+        assert!(!out.contains("clock") && !out.contains("data"));
+    }
+}
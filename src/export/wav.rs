@@ -0,0 +1,213 @@
+use crate::{
+    analysis::sampler::{Sampler, Shaping},
+    coder::SigElement,
+};
+
+// 16-bit mono PCM, the highest sample magnitude reaching full scale.
+#[derive(Debug, Clone, Copy)]
+pub struct Wav {
+    bit_time: f64,
+    sample_rate: u32,
+    bit_rate: Option<f64>,
+    shaping: Shaping,
+}
+
+impl Wav {
+    pub const DEFAULT_SAMPLE_RATE: u32 = 48_000;
+    pub const MAX_SAMPLE_RATE: u32 = 768_000;
+    const HEADER_LEN: u64 = 44;
+    const BITS_PER_SAMPLE: u16 = 16;
+
+    #[inline]
+    pub fn new(bit_time: f64) -> Self {
+        Self {
+            bit_time,
+            sample_rate: Self::DEFAULT_SAMPLE_RATE,
+            bit_rate: None,
+            shaping: Shaping::None,
+        }
+    }
+
+    #[inline]
+    pub fn with_sample_rate(mut self, rate: u32) -> Self {
+        self.sample_rate = rate.clamp(1, Self::MAX_SAMPLE_RATE);
+        self
+    }
+
+    // Plays the waveform at another bit rate than the coder's.
+    #[inline]
+    pub fn with_bit_rate(mut self, rate: f64) -> Self {
+        self.bit_rate = Some(rate);
+        self
+    }
+
+    #[inline]
+    pub fn with_shaping(mut self, shaping: Shaping) -> Self {
+        self.shaping = shaping;
+        self
+    }
+
+    #[inline]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    #[inline]
+    pub fn bit_rate(&self) -> f64 {
+        self.bit_rate.unwrap_or(self.bit_time.recip())
+    }
+
+    #[inline]
+    pub fn samples_per_bit(&self) -> f64 {
+        self.sampler().samples_per_bit()
+    }
+
+    // Size of the rendered file in bytes, which must fit the 32-bit RIFF
+    // length fields.
+    pub fn file_len(&self, sigs: &[SigElement]) -> u64 {
+        let end = sigs.last().map(|sig| sig.tf()).unwrap_or_default() * self.scale();
+        let samples = (end * self.sample_rate as f64).round() as u64;
+        Self::HEADER_LEN.saturating_add(samples.saturating_mul(2))
+    }
+
+    pub fn render(&self, sigs: &[SigElement]) -> Result<Vec<u8>, String> {
+        let too_long = || "WAV output would exceed 4 GiB".to_owned();
+        if self.file_len(sigs) - 8 > u32::MAX as u64 {
+            return Err(too_long());
+        }
+
+        let scale = self.scale();
+        let sigs = sigs
+            .iter()
+            .map(|sig| SigElement::new(sig.ti() * scale, sig.tf() * scale, sig.lvl()))
+            .collect::<Box<[_]>>();
+        let samples = self.sampler().sample(&sigs);
+        let peak = samples.iter().map(|x| x.abs()).fold(0.0, f64::max);
+        let gain = if peak > 0.0 { peak.recip() } else { 0.0 };
+
+        let block_align = Self::BITS_PER_SAMPLE / 8;
+        let byte_rate = self
+            .sample_rate
+            .checked_mul(block_align as u32)
+            .ok_or_else(|| format!("sample rate {} is too high", self.sample_rate))?;
+        let data_len = u32::try_from(samples.len() * block_align as usize)
+            .ok()
+            .filter(|len| len.checked_add(36).is_some())
+            .ok_or_else(too_long)?;
+
+        let mut out = Vec::with_capacity(Self::HEADER_LEN as usize + data_len as usize);
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&self.sample_rate.to_le_bytes());
+        out.extend_from_slice(&byte_rate.to_le_bytes());
+        out.extend_from_slice(&block_align.to_le_bytes());
+        out.extend_from_slice(&Self::BITS_PER_SAMPLE.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        for x in samples {
+            let pcm = (x * gain * i16::MAX as f64).round() as i16;
+            out.extend_from_slice(&pcm.to_le_bytes());
+        }
+
+        Ok(out)
+    }
+
+    #[inline]
+    fn scale(&self) -> f64 {
+        self.bit_rate().recip() / self.bit_time
+    }
+
+    #[inline]
+    fn sampler(&self) -> Sampler {
+        Sampler::new()
+            .with_bit_rate(self.bit_rate())
+            .with_sample_rate(self.sample_rate as f64)
+            .with_shaping(self.shaping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wav;
+    use crate::analysis::sampler::Shaping;
+    use crate::coder::{LineCoder, ami::Ami, nrz::Nrzl};
+
+    fn pcm(wav: &[u8]) -> Vec<i16> {
+        wav[44..]
+            .chunks(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_wav_header_and_levels() {
+        let enc = Ami::new().with_amplitude(2.5).with_bit_rate(1e3);
+        let wav = Wav::new(enc.bit_time())
+            .with_sample_rate(8000)
+            .render(&enc.encode(&[1, 0, 1]))
+            .unwrap();
+
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 8000);
+        assert_eq!(u32::from_le_bytes(wav[28..32].try_into().unwrap()), 16_000);
+        assert_eq!(u16::from_le_bytes([wav[34], wav[35]]), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 48);
+        assert_eq!(
+            u32::from_le_bytes(wav[4..8].try_into().unwrap()) as usize,
+            wav.len() - 8
+        );
+
+        let pcm = pcm(&wav);
+        assert_eq!(pcm.len(), 24);
+        assert!(pcm[..8].iter().all(|&x| x == i16::MAX));
+        assert!(pcm[8..16].iter().all(|&x| x == 0));
+        assert!(pcm[16..].iter().all(|&x| x == -i16::MAX));
+    }
+
+    #[test]
+    fn test_wav_retimes_to_bit_rate() {
+        let enc = Nrzl::new();
+        let wav = Wav::new(enc.bit_time())
+            .with_sample_rate(48_000)
+            .with_bit_rate(1200.0);
+        assert_eq!(wav.samples_per_bit(), 40.0);
+
+        let pcm = pcm(&wav.render(&enc.encode(&[0, 1])).unwrap());
+        assert_eq!(pcm.len(), 80);
+        assert!(pcm[..40].iter().all(|&x| x == -i16::MAX));
+        assert!(pcm[40..].iter().all(|&x| x == i16::MAX));
+        assert_eq!(Wav::new(1e-3).render(&[]).unwrap().len(), 44);
+    }
+
+    #[test]
+    fn test_wav_shaping_and_size_limits() {
+        let enc = Nrzl::new();
+        let sigs = enc.encode(&[1, 1, 0, 0, 1, 1]);
+        let wav = Wav::new(enc.bit_time())
+            .with_sample_rate(8000)
+            .with_bit_rate(500.0)
+            .with_shaping(Shaping::Ramp(0.5));
+        assert_eq!(wav.file_len(&sigs), 44 + 2 * 96);
+
+        let pcm = pcm(&wav.render(&sigs).unwrap());
+        assert_eq!(pcm[8], i16::MAX);
+        assert!(pcm[30..34].iter().all(|&x| x.abs() < i16::MAX));
+
+        let long = Wav::new(enc.bit_time())
+            .with_sample_rate(Wav::MAX_SAMPLE_RATE)
+            .with_bit_rate(1.0);
+        let sigs = enc.encode(&[0; 4096]);
+        assert!(long.file_len(&sigs) > u32::MAX as u64);
+        assert!(long.render(&sigs).is_err());
+        assert_eq!(
+            Wav::new(1.0).with_sample_rate(u32::MAX).sample_rate(),
+            768_000
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use strum::IntoEnumIterator;
+use strum_macros::{AsRefStr, EnumIter, FromRepr};
+
+// Fibonacci LFSR over the ITU-T O.150 polynomials x^order + x^tap + 1. The
+// state is only `order` bits wide, at most 32, and a zero seed, which would
+// lock the register, falls back to all ones.
+#[derive(Debug, Clone)]
+pub struct Prbs {
+    order: u32,
+    tap: u32,
+    state: u32,
+    inverted: bool,
+}
+
+impl Prbs {
+    // `None` unless the tap lies strictly inside the register.
+    pub fn new(order: u32, tap: u32, seed: u32) -> Option<Self> {
+        if order > 32 || tap == 0 || tap >= order {
+            return None;
+        }
+
+        let mask = Self::mask(order);
+        let state = match seed & mask {
+            0 => mask,
+            state => state,
+        };
+        Some(Self {
+            order,
+            tap,
+            state,
+            inverted: false,
+        })
+    }
+
+    // O.150 sends the longer sequences inverted, so their longest run is
+    // `order` zeros rather than ones.
+    #[inline]
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    #[inline]
+    fn mask(order: u32) -> u32 {
+        u32::MAX >> (32 - order)
+    }
+}
+
+impl Iterator for Prbs {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let bit = ((self.state >> (self.order - 1)) ^ (self.state >> (self.tap - 1))) & 1;
+        self.state = ((self.state << 1) | bit) & Self::mask(self.order);
+        Some((bit as u8) ^ self.inverted as u8)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr, EnumIter, FromRepr)]
+pub enum Pattern {
+    #[default]
+    #[strum(serialize = "PRBS7")]
+    Prbs7,
+    #[strum(serialize = "PRBS15")]
+    Prbs15,
+    #[strum(serialize = "PRBS23")]
+    Prbs23,
+    #[strum(serialize = "PRBS31")]
+    Prbs31,
+    #[strum(serialize = "All zeros")]
+    Zeros,
+    #[strum(serialize = "All ones")]
+    Ones,
+    #[strum(serialize = "Alternating")]
+    Alternating,
+    #[strum(serialize = "1 in 8")]
+    OneInEight,
+    #[strum(serialize = "3 in 24")]
+    ThreeInTwentyFour,
+}
+
+impl Pattern {
+    const ONE_IN_EIGHT: &[u8] = &[0, 1, 0, 0, 0, 0, 0, 0];
+    const THREE_IN_TWENTY_FOUR: &[u8] = &[
+        0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Pattern::Prbs7 => "prbs7",
+            Pattern::Prbs15 => "prbs15",
+            Pattern::Prbs23 => "prbs23",
+            Pattern::Prbs31 => "prbs31",
+            Pattern::Zeros => "zeros",
+            Pattern::Ones => "ones",
+            Pattern::Alternating => "alt",
+            Pattern::OneInEight => "1in8",
+            Pattern::ThreeInTwentyFour => "3in24",
+        }
+    }
+
+    #[inline]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::iter().find(|pat| pat.id().eq_ignore_ascii_case(id))
+    }
+
+    #[inline]
+    pub fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+
+    pub fn prbs(self, seed: u32) -> Option<Prbs> {
+        let (order, tap) = match self {
+            Pattern::Prbs7 => (7, 6),
+            Pattern::Prbs15 => (15, 14),
+            Pattern::Prbs23 => (23, 18),
+            Pattern::Prbs31 => (31, 28),
+            _ => return None,
+        };
+        Prbs::new(order, tap, seed).map(|prbs| prbs.with_inverted(order > 7))
+    }
+
+    #[inline]
+    pub fn is_prbs(self) -> bool {
+        self.prbs(0).is_some()
+    }
+
+    // `seed` only affects the PRBS patterns, the stress patterns being fixed.
+    pub fn generate(self, len: usize, seed: u32) -> Box<[u8]> {
+        if let Some(prbs) = self.prbs(seed) {
+            return prbs.take(len).collect();
+        }
+
+        let period: &[u8] = match self {
+            Pattern::Zeros => &[0],
+            Pattern::Ones => &[1],
+            Pattern::Alternating => &[1, 0],
+            Pattern::OneInEight => Self::ONE_IN_EIGHT,
+            _ => Self::THREE_IN_TWENTY_FOUR,
+        };
+        period.iter().copied().cycle().take(len).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::{Pattern, Prbs};
+
+    fn longest_run(bits: &[u8], bit: u8) -> usize {
+        bits.split(|&b| b != bit)
+            .map(<[u8]>::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_prbs_maximal_length() {
+        for (pat, order, mark) in [(Pattern::Prbs7, 7, 1), (Pattern::Prbs15, 15, 0)] {
+            let period = (1 << order) - 1;
+            let bits = pat.generate(2 * period, 1);
+            let (first, second) = bits.split_at(period);
+            assert_eq!(first, second);
+            assert_eq!(
+                first.iter().filter(|&&b| b == mark).count(),
+                1 << (order - 1)
+            );
+            assert_eq!(longest_run(first, mark), order);
+            assert_eq!(longest_run(first, 1 - mark), order - 1);
+            assert!((1..period).all(|shift| bits[shift..shift + period] != *first));
+        }
+    }
+
+    #[test]
+    fn test_prbs_inverted_output() {
+        for (pat, order, tap, inverted) in [
+            (Pattern::Prbs7, 7, 6, false),
+            (Pattern::Prbs15, 15, 14, true),
+            (Pattern::Prbs23, 23, 18, true),
+            (Pattern::Prbs31, 31, 28, true),
+        ] {
+            let plain = Prbs::new(order, tap, 1).unwrap().take(64);
+            let bits = pat.generate(64, 1);
+            assert!(
+                plain.zip(&bits).all(|(a, &b)| (a != b) == inverted),
+                "{pat:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_prbs_rejects_invalid_registers() {
+        for (order, tap) in [
+            (0, 0),
+            (1, 0),
+            (7, 0),
+            (7, 7),
+            (7, 9),
+            (33, 28),
+            (u32::MAX, 1),
+        ] {
+            assert!(Prbs::new(order, tap, 1).is_none(), "{order} {tap}");
+        }
+        assert_eq!(Prbs::new(32, 22, 0).unwrap().take(64).count(), 64);
+        assert_eq!(
+            Prbs::new(2, 1, 0).unwrap().take(3).collect::<Vec<_>>(),
+            [0, 1, 1]
+        );
+    }
+
+    #[test]
+    fn test_prbs_seed() {
+        let bits = Pattern::Prbs31.generate(64, 1);
+        assert_eq!(bits.len(), 64);
+        assert_ne!(bits, Pattern::Prbs31.generate(64, 0xdead_beef));
+        assert_eq!(
+            Pattern::Prbs23.generate(64, 0),
+            Pattern::Prbs23.generate(64, 0x7f_ffff)
+        );
+    }
+
+    #[test]
+    fn test_stress_patterns() {
+        assert_eq!(
+            Pattern::Alternating.generate(5, 0).as_ref(),
+            &[1, 0, 1, 0, 1]
+        );
+        assert!(Pattern::Zeros.generate(16, 0).iter().all(|&b| b == 0));
+
+        let t1 = Pattern::ThreeInTwentyFour.generate(48, 0);
+        assert_eq!(t1.iter().filter(|&&b| b == 1).count(), 6);
+        assert_eq!(longest_run(&t1, 0), 15);
+        assert_eq!(Pattern::OneInEight.generate(16, 0).iter().sum::<u8>(), 2);
+
+        for pat in Pattern::iter() {
+            assert_eq!(Pattern::from_id(pat.id()), Some(pat));
+            assert_eq!(pat.generate(33, 7).len(), 33);
+        }
+    }
+}
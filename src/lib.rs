@@ -0,0 +1,23 @@
+//! Line coding schemes and the tools built around them: waveform analysis,
+//! channel simulation, bit pattern generators and waveform exporters.
+//!
+//! The interactive playground and the `lcv` command are behind the default
+//! `tui` feature, so depending on the crate with `default-features = false`
+//! only brings in the coders.
+//!
+//! ```
+//! use lcv::{LineCoder, coder::hdb3::Hdb3, util};
+//!
+//! let bits = util::parse_bits("1100001").unwrap();
+//! let sigs = Hdb3::new().encode(&bits);
+//! assert_eq!(sigs.len(), bits.len());
+//! ```
+
+pub mod analysis;
+pub mod channel;
+pub mod coder;
+pub mod export;
+pub mod generator;
+pub mod util;
+
+pub use coder::{LineCoder, LineDecoder, SigElement, StreamEncoder};
@@ -1,13 +1,57 @@
+#[cfg(feature = "tui")]
 mod app;
-mod coder;
+mod cli;
+#[cfg(feature = "tui")]
 mod term;
+#[cfg(feature = "tui")]
 mod trace;
+#[cfg(feature = "tui")]
 mod tui;
-mod util;
 
+use std::process;
+
+use clap::Parser;
+
+use lcv::coder::registry::Registry;
+
+#[cfg(feature = "tui")]
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = cli::Cli::parse();
+    let registry = registry(&cli);
+    if let Some(command) = cli.command {
+        run(command, &registry);
+        return Ok(());
+    }
+
     trace::setup_tracing()?;
     trace::setup_panic_hook();
-    app::App::build()?.run().await
+    app::App::build(registry)?.run().await
+}
+
+#[cfg(not(feature = "tui"))]
+fn main() {
+    let cli = cli::Cli::parse();
+    let registry = registry(&cli);
+    match cli.command {
+        Some(command) => run(command, &registry),
+        None => {
+            eprintln!("error: lcv was built without the `tui` feature, run one of its subcommands");
+            process::exit(2);
+        }
+    }
+}
+
+fn registry(cli: &cli::Cli) -> Registry {
+    cli.registry().unwrap_or_else(|err| {
+        eprintln!("error: {err:#}");
+        process::exit(1);
+    })
+}
+
+fn run(command: cli::Command, registry: &Registry) {
+    if let Err(err) = cli::run(command, registry) {
+        eprintln!("error: {err:#}");
+        process::exit(1);
+    }
 }
@@ -1,5 +1,6 @@
 use std::{env, fs, panic, path::PathBuf, process, sync::LazyLock};
 
+use lcv::coder::registry::Registry;
 use tracing::level_filters::LevelFilter;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt};
@@ -31,7 +32,7 @@ pub(super) fn setup_panic_hook() {
         .create_panic_handler();
 
     panic::set_hook(Box::new(move |info| {
-        if let Err(err) = Tui::build().map(|mut term| term.exit()) {
+        if let Err(err) = Tui::build(Registry::builtin()).map(|mut term| term.exit()) {
             tracing::error!("Error transitioning terminal to normal mode: {err}");
         }
 
@@ -4,7 +4,14 @@ mod params;
 mod style;
 mod visualizer;
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 use banner::Banner;
 use crossterm::{
@@ -20,9 +27,19 @@ use ratatui::{
     layout::{Constraint, Flex, Layout, Rect},
     prelude::CrosstermBackend,
 };
-use tokio::{sync::mpsc, time::Instant};
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task,
+    time::Instant,
+};
 use visualizer::Visualizer;
 
+use lcv::{
+    analysis::{ber::BerPoint, eye::Eye, psd::Spectrum, sampler::Sampler, stats::Stats},
+    channel::Channel,
+    coder::{BitCell, SigElement, registry::Registry},
+};
+
 trait ActiveWidget {
     fn init(&mut self) {}
 
@@ -45,6 +62,94 @@ struct Ctx<'a> {
     _phantom: PhantomData<&'a ()>,
 }
 
+#[derive(Debug, Default)]
+struct Waveform {
+    label: String,
+    sigs: Box<[SigElement]>,
+    cells: Box<[BitCell]>,
+}
+
+#[derive(Debug, Default)]
+struct Encoding {
+    label: String,
+    pinned: Box<[Waveform]>,
+    sigs: Box<[SigElement]>,
+    cells: Box<[BitCell]>,
+    stats: Stats,
+    random_sigs: Option<Box<[SigElement]>>,
+    bit_rate: f64,
+    channel: Channel,
+    sampler: Sampler,
+    ber_id: usize,
+}
+
+// What the visualizer derives from an encoding in the background.
+#[derive(Debug, Default)]
+struct Analysis {
+    received: Box<[(f64, f64)]>,
+    spectrum: Spectrum,
+    spectrum_points: Box<[(f64, f64)]>,
+    eye: Eye,
+    eye_points: Box<[(f64, f64)]>,
+}
+
+impl Encoding {
+    #[inline]
+    fn analysis_sigs(&self) -> &[SigElement] {
+        self.random_sigs.as_deref().unwrap_or(&self.sigs)
+    }
+}
+
+// Sent to the visualizer. Background results carry the id of the job that
+// computed them.
+#[derive(Debug)]
+enum Update {
+    Encoding(Box<Encoding>),
+    Ber(usize, Box<[BerPoint]>),
+    Analysis(usize, Box<Analysis>),
+}
+
+// Work too slow for the UI thread. Each run waits for its inputs to settle,
+// and is dropped if newer ones arrived meanwhile.
+#[derive(Debug, Default)]
+struct Job {
+    id: usize,
+    latest: Arc<AtomicUsize>,
+}
+
+impl Job {
+    const DEBOUNCE: Duration = Duration::from_millis(150);
+
+    #[inline]
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn spawn(
+        &mut self,
+        tx: &UnboundedSender<Update>,
+        work: impl FnOnce(usize) -> Update + Send + 'static,
+    ) {
+        self.id += 1;
+        self.latest.store(self.id, Ordering::Relaxed);
+
+        let id = self.id;
+        let latest = Arc::clone(&self.latest);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Self::DEBOUNCE).await;
+            if latest.load(Ordering::Relaxed) != id {
+                return;
+            }
+
+            match task::spawn_blocking(move || work(id)).await {
+                Ok(update) => _ = tx.send(update),
+                Err(err) => tracing::error!("Background job failed: {err}"),
+            }
+        });
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 enum TuiMode {
     #[default]
@@ -76,15 +181,15 @@ pub struct Tui {
 }
 
 impl Tui {
-    pub fn build() -> anyhow::Result<Self> {
+    pub fn build(registry: Registry) -> anyhow::Result<Self> {
         let (sig_tx, sig_rx) = mpsc::unbounded_channel();
         Ok(Self {
             term: ratatui::Terminal::new(CrosstermBackend::new(std::io::stdout()))?,
             mode: TuiMode::default(),
             should_quit: false,
-            params: Parameters::new(sig_tx),
+            visualizer: Visualizer::new(sig_tx.clone(), sig_rx),
+            params: Parameters::new(sig_tx, registry),
             banner: Banner,
-            visualizer: Visualizer::new(sig_rx),
             footer: Footer,
         })
     }
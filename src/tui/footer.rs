@@ -34,6 +34,12 @@ impl ActiveWidget for Footer {
             Span::raw(" | ").style(Theme::BORDER_TERNARY),
             Span::raw("<Left/Right>").patch_style(Theme::HINT),
             Span::raw(" to scroll waveform").style(Theme::TEXT),
+            Span::raw(" | ").style(Theme::BORDER_TERNARY),
+            Span::raw("<v>").patch_style(Theme::HINT),
+            Span::raw(" to swap view").style(Theme::TEXT),
+            Span::raw(" | ").style(Theme::BORDER_TERNARY),
+            Span::raw("<e/p/w>").patch_style(Theme::HINT),
+            Span::raw(" to export VCD/SVG/WAV").style(Theme::TEXT),
         ])
         .left_aligned();
 
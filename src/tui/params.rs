@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt::Debug};
+use std::{cell::RefCell, fmt::Debug, fs::File, io::Read};
 
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
@@ -7,7 +7,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{
         Block, BorderType, List, ListItem, ListState, Padding, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState,
+        ScrollbarOrientation, ScrollbarState, Wrap,
     },
 };
 use strum::IntoEnumIterator;
@@ -15,20 +15,24 @@ use strum_macros::{AsRefStr, EnumIter, FromRepr};
 use tokio::sync::mpsc::UnboundedSender;
 use tui_input::{Input, InputRequest};
 
-use crate::{
+use lcv::{
+    analysis::{
+        ber,
+        sampler::{Sampler, Shaping},
+        stats::Stats,
+    },
+    channel::{Channel, FilterOrder, LowPass},
     coder::{
-        LineCoder, SigElement,
-        ami::Ami,
-        hdb3::Hdb3,
-        manch::Manchester,
-        mlt3::Mlt3,
-        nrz::{Nrzi, Nrzl},
-        rz::Rz,
+        LineCoder,
+        catalog::{self, BlockName, Settings},
+        registry::{Entry, Registry, Schema},
+        stream::Incremental,
     },
-    util,
+    generator::Pattern,
+    util::{self, BitOrder, InputFormat, XorShift},
 };
 
-use super::{ActiveWidget, Ctx, style::Theme};
+use super::{ActiveWidget, Ctx, Encoding, Job, Update, Waveform, style::Theme};
 
 #[derive(Debug, Default, Clone, Copy)]
 enum Mode {
@@ -37,55 +41,241 @@ enum Mode {
     Insert,
 }
 
-#[derive(Debug, Default, Clone, Copy, AsRefStr)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr, FromRepr)]
 enum Focus {
     Method,
     #[default]
     Bits,
+    Settings,
 }
 
-#[derive(Debug, Clone, Default, Copy, PartialEq, Eq, EnumIter, AsRefStr, FromRepr)]
-enum CoderName {
-    #[default]
-    #[strum(serialize = "NRZ-L")]
-    Nrzl,
-    #[strum(serialize = "NRZ-I")]
-    Nrzi,
-    #[strum(serialize = "RZ")]
-    Rz,
-    #[strum(serialize = "Manchester 802.3")]
-    Manchester,
-    #[strum(serialize = "HDB3")]
-    Hdb3,
-    #[strum(serialize = "MLT-3")]
-    Mlt3,
-    #[strum(serialize = "AMI")]
-    Ami,
+impl Focus {
+    #[inline]
+    fn prev(self) -> Self {
+        Self::from_repr((self as usize).saturating_sub(1)).unwrap_or(self)
+    }
+
+    #[inline]
+    fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or(self)
+    }
 }
 
-impl CoderName {
-    fn to_raw(self) -> Box<dyn LineCoder> {
+#[derive(Debug, Default, Clone, Copy)]
+struct ChannelSettings {
+    noise: f64,
+    low_pass: usize,
+    order: FilterOrder,
+    attenuation: f64,
+    dc_block: usize,
+}
+
+impl ChannelSettings {
+    // Cutoffs relative to the bit rate.
+    const LOW_PASS_CUTOFFS: [Option<f64>; 5] = [None, Some(2.0), Some(1.0), Some(0.5), Some(0.25)];
+    const DC_BLOCK_CUTOFFS: [Option<f64>; 3] = [None, Some(0.01), Some(0.05)];
+
+    fn to_channel(self, bit_rate: f64) -> Channel {
+        let mut channel = Channel::new()
+            .with_noise(self.noise)
+            .with_attenuation(self.attenuation);
+        if let Some(cutoff) = Self::LOW_PASS_CUTOFFS[self.low_pass] {
+            channel = channel.with_low_pass(LowPass::new(cutoff * bit_rate).with_order(self.order));
+        }
+        if let Some(cutoff) = Self::DC_BLOCK_CUTOFFS[self.dc_block] {
+            channel = channel.with_dc_block(cutoff * bit_rate);
+        }
+
+        channel
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, AsRefStr)]
+enum Setting {
+    #[strum(serialize = "Bit rate")]
+    BitRate,
+    #[strum(serialize = "Amplitude")]
+    Amplitude,
+    #[strum(serialize = "Initial state")]
+    InitState,
+    #[strum(serialize = "Duty cycle")]
+    Duty,
+    #[strum(serialize = "Pulse shape")]
+    Shaping,
+    #[strum(serialize = "Noise (rms)")]
+    Noise,
+    #[strum(serialize = "Low-pass")]
+    LowPass,
+    #[strum(serialize = "Filter order")]
+    FilterOrder,
+    #[strum(serialize = "Attenuation")]
+    Attenuation,
+    #[strum(serialize = "DC block")]
+    DcBlock,
+}
+
+impl Setting {
+    const MAX_BIT_RATE: f64 = 1e9;
+    const MAX_AMPLITUDE: f64 = 10.0;
+    const AMPLITUDE_STEP: f64 = 0.5;
+    const DUTY_STEP: f64 = 0.05;
+    const MAX_NOISE: f64 = 2.0;
+    const NOISE_STEP: f64 = 0.05;
+    const MAX_ATTENUATION: f64 = 40.0;
+    const ATTENUATION_STEP: f64 = 1.0;
+
+    fn of(schema: &Schema) -> impl Iterator<Item = Self> + use<> {
+        let init_state = !schema.init_states().is_empty();
+        let duty = schema.has_duty();
+        Self::iter().filter(move |setting| match setting {
+            Setting::InitState => init_state,
+            Setting::Duty => duty,
+            _ => true,
+        })
+    }
+
+    fn adjust(self, params: &mut Parameters, up: bool) {
+        let step = |val: f64, step: f64, min: f64, max: f64| {
+            let val = if up { val + step } else { val - step };
+            ((val / step).round() * step).clamp(min, max)
+        };
+        let cycle = |idx: usize, n: usize| {
+            let n = n.max(1);
+            if up { (idx + 1) % n } else { (idx + n - 1) % n }
+        };
+
+        let init_states = params.entry().schema().init_states().len();
+        let settings = &mut params.settings;
+        let channel = &mut params.channel;
         match self {
-            CoderName::Nrzl => Nrzl::new().boxed(),
-            CoderName::Nrzi => Nrzi::new().boxed(),
-            CoderName::Rz => Rz::new().boxed(),
-            CoderName::Manchester => Manchester::new().boxed(),
-            CoderName::Hdb3 => Hdb3::new().boxed(),
-            CoderName::Mlt3 => Mlt3::new().boxed(),
-            CoderName::Ami => Ami::new().boxed(),
+            Setting::BitRate => settings.bit_rate = Self::step_125(settings.bit_rate, up),
+            Setting::Amplitude => {
+                settings.amplitude = step(
+                    settings.amplitude,
+                    Self::AMPLITUDE_STEP,
+                    Self::AMPLITUDE_STEP,
+                    Self::MAX_AMPLITUDE,
+                )
+            }
+            Setting::InitState => settings.init_state = cycle(settings.init_state, init_states),
+            Setting::Duty => {
+                settings.duty = step(
+                    settings.duty,
+                    Self::DUTY_STEP,
+                    Self::DUTY_STEP,
+                    1.0 - Self::DUTY_STEP,
+                )
+            }
+            Setting::Shaping => params.shaping = cycle(params.shaping, Parameters::SHAPINGS.len()),
+            Setting::Noise => {
+                channel.noise = step(channel.noise, Self::NOISE_STEP, 0.0, Self::MAX_NOISE)
+            }
+            Setting::LowPass => {
+                channel.low_pass = cycle(channel.low_pass, ChannelSettings::LOW_PASS_CUTOFFS.len())
+            }
+            Setting::FilterOrder => {
+                channel.order = match channel.order {
+                    FilterOrder::First => FilterOrder::Second,
+                    FilterOrder::Second => FilterOrder::First,
+                }
+            }
+            Setting::Attenuation => {
+                channel.attenuation = step(
+                    channel.attenuation,
+                    Self::ATTENUATION_STEP,
+                    0.0,
+                    Self::MAX_ATTENUATION,
+                )
+            }
+            Setting::DcBlock => {
+                channel.dc_block = cycle(channel.dc_block, ChannelSettings::DC_BLOCK_CUTOFFS.len())
+            }
+        }
+    }
+
+    fn value(self, params: &Parameters) -> String {
+        let settings = &params.settings;
+        let channel = &params.channel;
+        let cutoff = |cutoff: Option<f64>| match cutoff {
+            Some(cutoff) => format!("{cutoff} Rb"),
+            None => "Off".to_owned(),
+        };
+
+        match self {
+            Setting::BitRate => util::fmt_si(settings.bit_rate, "bps"),
+            Setting::Amplitude => format!("{:.1} V", settings.amplitude),
+            Setting::InitState => params
+                .entry()
+                .schema()
+                .init_states()
+                .get(settings.init_state)
+                .cloned()
+                .unwrap_or_default(),
+            Setting::Duty => format!("{:.0} %", settings.duty * 100.0),
+            Setting::Shaping => match Parameters::SHAPINGS[params.shaping] {
+                Shaping::None => "Off".to_owned(),
+                Shaping::Ramp(rise) => format!("Ramp {rise} Tb"),
+                Shaping::RaisedCosine(beta) => format!("RC β {beta}"),
+            },
+            Setting::Noise => format!("{:.2} V", channel.noise),
+            Setting::LowPass => cutoff(ChannelSettings::LOW_PASS_CUTOFFS[channel.low_pass]),
+            Setting::FilterOrder => match channel.order {
+                FilterOrder::First => "1st".to_owned(),
+                FilterOrder::Second => "2nd".to_owned(),
+            },
+            Setting::Attenuation => format!("{:.0} dB", channel.attenuation),
+            Setting::DcBlock => cutoff(ChannelSettings::DC_BLOCK_CUTOFFS[channel.dc_block]),
         }
     }
+
+    fn step_125(rate: f64, up: bool) -> f64 {
+        let exp = rate.log10().floor();
+        let base = 10f64.powf(exp);
+        let mant = (rate / base).round() as u32;
+        let next = match (mant, up) {
+            (1, true) => 2.0 * base,
+            (2, true) => 5.0 * base,
+            (_, true) => 10.0 * base,
+            (1, false) => 0.5 * base,
+            (2, false) => base,
+            (_, false) => 2.0 * base,
+        };
+
+        next.clamp(1.0, Self::MAX_BIT_RATE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pin {
+    coder: usize,
+    block_name: BlockName,
 }
 
 pub(super) struct Parameters {
     mode: Mode,
     focus: Focus,
-    coder_name: CoderName,
+    registry: Registry,
+    coder: usize,
+    block_name: BlockName,
+    settings: Settings,
+    channel: ChannelSettings,
+    shaping: usize,
     raw_coder: Box<dyn LineCoder>,
+    encoder: Incremental,
+    pinned: Vec<Pin>,
+    pin_coders: Vec<Box<dyn LineCoder>>,
+    ber_job: Job,
     bits_input: Input,
+    input_format: InputFormat,
+    bit_order: BitOrder,
+    input_bits: Option<usize>,
+    pattern: Option<Pattern>,
+    pattern_len: usize,
+    pattern_seed: u32,
     scroll_state: RefCell<ScrollbarState>,
     list_state: RefCell<ListState>,
-    sig_tx: UnboundedSender<Box<[SigElement]>>,
+    settings_state: RefCell<ListState>,
+    sig_tx: UnboundedSender<Update>,
 }
 
 impl Debug for Parameters {
@@ -93,47 +283,145 @@ impl Debug for Parameters {
         f.debug_struct("Parameters")
             .field("mode", &self.mode)
             .field("focus", &self.focus)
-            .field("coder", &self.coder_name)
+            .field("coder", &self.entry().id())
+            .field("block", &self.block_name)
+            .field("settings", &self.settings)
+            .field("channel", &self.channel)
+            .field("shaping", &self.sampler().shaping())
+            .field("pinned", &self.pinned.len())
             .field("bits_input", &self.bits_input)
+            .field("input_format", &self.input_format)
+            .field("bit_order", &self.bit_order)
+            .field("pattern", &self.pattern)
             .field("sig_tx", &self.sig_tx)
             .finish()
     }
 }
 
 impl Parameters {
-    pub(super) fn new(sig_tx: UnboundedSender<Box<[SigElement]>>) -> Self {
-        let coder_name = CoderName::default();
+    const MIN_ANALYSIS_BITS: usize = 256;
+    const RANDOM_ANALYSIS_BITS: usize = 8192;
+    const BER_BITS: usize = 16_000;
+    const BER_SNR_STEP: f64 = 2.0;
+    const BER_SNR_MAX: f64 = 16.0;
+    const MAX_PINNED: usize = 4;
+    const MAX_FILE_BYTES: u64 = 4096;
+    const DEFAULT_PATTERN_BITS: usize = 128;
+    const MIN_PATTERN_BITS: usize = 8;
+    const MAX_PATTERN_BITS: usize = 4096;
+    const SHAPINGS: [Shaping; 4] = [
+        Shaping::None,
+        Shaping::Ramp(0.2),
+        Shaping::RaisedCosine(0.35),
+        Shaping::RaisedCosine(1.0),
+    ];
+
+    pub(super) fn new(sig_tx: UnboundedSender<Update>, registry: Registry) -> Self {
+        let block_name = BlockName::default();
+        let entry = &registry.entries()[0];
+        let settings = Settings {
+            init_state: entry.schema().def_init_state(),
+            ..Default::default()
+        };
+        let raw_coder = catalog::build(entry, block_name, &settings);
         Self {
             sig_tx,
-            coder_name,
-            raw_coder: coder_name.to_raw(),
+            coder: 0,
+            block_name,
+            settings,
+            channel: Default::default(),
+            shaping: 0,
+            encoder: Incremental::new(raw_coder.as_ref()),
+            raw_coder,
+            pinned: Vec::new(),
+            pin_coders: Vec::new(),
+            ber_job: Default::default(),
             mode: Default::default(),
             focus: Default::default(),
             bits_input: Default::default(),
-            scroll_state: RefCell::new(ScrollbarState::new(CoderName::iter().count())),
+            input_format: Default::default(),
+            bit_order: Default::default(),
+            input_bits: Some(0),
+            pattern: None,
+            pattern_len: Self::DEFAULT_PATTERN_BITS,
+            pattern_seed: u32::MAX,
+            scroll_state: RefCell::new(ScrollbarState::new(registry.entries().len())),
             list_state: RefCell::new(ListState::default().with_selected(Some(0))),
+            settings_state: RefCell::new(ListState::default().with_selected(Some(0))),
+            registry,
         }
     }
 
+    #[inline]
+    fn entry(&self) -> &Entry {
+        &self.registry.entries()[self.coder]
+    }
+
     fn handle_key_normal(&mut self, key_code: KeyCode) {
         match key_code {
             KeyCode::Enter if matches!(self.focus, Focus::Bits) => self.mode = Mode::Insert,
-            KeyCode::Left => self.focus = Focus::Method,
-            KeyCode::Right => self.focus = Focus::Bits,
+            KeyCode::Char('f') if matches!(self.focus, Focus::Bits) => {
+                self.input_format = self.input_format.next();
+                self.pattern = None;
+                self.parse_and_send();
+            }
+            KeyCode::Char('g') if matches!(self.focus, Focus::Bits) => {
+                self.pattern = Some(self.pattern.map_or_else(Pattern::default, Pattern::next));
+                self.fill_pattern();
+            }
+            KeyCode::Char('+' | '=') if matches!(self.focus, Focus::Bits) => {
+                self.resize_pattern(true)
+            }
+            KeyCode::Char('-') if matches!(self.focus, Focus::Bits) => self.resize_pattern(false),
+            KeyCode::Char('r')
+                if matches!(self.focus, Focus::Bits)
+                    && self.pattern.is_some_and(Pattern::is_prbs) =>
+            {
+                self.pattern_seed = XorShift::new(self.pattern_seed.into()).next_u64() as u32;
+                self.fill_pattern();
+            }
+            KeyCode::Char('o')
+                if matches!(self.focus, Focus::Bits) && self.input_format.has_bit_order() =>
+            {
+                self.bit_order = self.bit_order.toggle();
+                self.parse_and_send();
+            }
+            KeyCode::Left => self.focus = self.focus.prev(),
+            KeyCode::Right => self.focus = self.focus.next(),
             KeyCode::Up if matches!(self.focus, Focus::Method) => self.prev_coder(),
             KeyCode::Down if matches!(self.focus, Focus::Method) => self.next_coder(),
+            KeyCode::Up if matches!(self.focus, Focus::Settings) => {
+                self.settings_state.borrow_mut().select_previous()
+            }
+            KeyCode::Down if matches!(self.focus, Focus::Settings) => {
+                self.select_setting(self.selected_setting() + 1)
+            }
+            KeyCode::Char('+' | '=') if matches!(self.focus, Focus::Settings) => {
+                self.adjust_setting(true)
+            }
+            KeyCode::Char('-') if matches!(self.focus, Focus::Settings) => {
+                self.adjust_setting(false)
+            }
+            KeyCode::Char('b') => self.next_block(),
+            KeyCode::Char('p') => self.toggle_pin(),
+            KeyCode::Char('c') if !self.pinned.is_empty() => {
+                self.pinned.clear();
+                self.rebuild_pins();
+                self.parse_and_send();
+            }
             _ => {}
         }
     }
 
     fn handle_key_insert(&mut self, key_code: KeyCode, modifiers: KeyModifiers) {
+        let len = self.bits_input.value().len();
         match key_code {
             KeyCode::Enter => self.mode = Mode::Normal,
             KeyCode::Left => _ = self.bits_input.handle(InputRequest::GoToPrevChar),
             KeyCode::Right => _ = self.bits_input.handle(InputRequest::GoToNextChar),
             KeyCode::Home => _ = self.bits_input.handle(InputRequest::GoToStart),
             KeyCode::End => _ = self.bits_input.handle(InputRequest::GoToEnd),
-            KeyCode::Char(ch @ '0' | ch @ '1') => {
+            KeyCode::Char(ch) if self.accepts(ch) => {
                 _ = self.bits_input.handle(InputRequest::InsertChar(ch));
                 self.parse_and_send();
             }
@@ -155,43 +443,261 @@ impl Parameters {
             }
             _ => {}
         }
+        if self.bits_input.value().len() != len {
+            self.pattern = None;
+        }
+    }
+
+    fn fill_pattern(&mut self) {
+        let Some(pattern) = self.pattern else {
+            return;
+        };
+
+        let bits = pattern.generate(self.pattern_len, self.pattern_seed);
+        self.input_format = InputFormat::Binary;
+        self.bits_input = Input::new(bits.iter().map(|&bit| char::from(b'0' + bit)).collect());
+        self.parse_and_send();
+    }
+
+    fn resize_pattern(&mut self, up: bool) {
+        if self.pattern.is_none() {
+            return;
+        }
+
+        self.pattern_len = if up {
+            self.pattern_len * 2
+        } else {
+            self.pattern_len / 2
+        }
+        .clamp(Self::MIN_PATTERN_BITS, Self::MAX_PATTERN_BITS);
+        self.fill_pattern();
+    }
+
+    fn accepts(&self, ch: char) -> bool {
+        match self.input_format {
+            InputFormat::Binary => matches!(ch, '0' | '1'),
+            InputFormat::Hex => ch.is_ascii_hexdigit(),
+            InputFormat::Text | InputFormat::Bytes => !ch.is_control(),
+        }
+    }
+
+    // In the file format the input holds a path, of which only the leading
+    // bytes are read so that a stray large file can't stall the interface.
+    fn read_bits(&self) -> Option<Box<[u8]>> {
+        let value = self.bits_input.value();
+        if value.is_empty() {
+            return Some(Default::default());
+        }
+
+        match self.input_format {
+            InputFormat::Bytes => {
+                let mut bytes = Vec::new();
+                File::open(value)
+                    .and_then(|file| file.take(Self::MAX_FILE_BYTES).read_to_end(&mut bytes))
+                    .ok()?;
+                InputFormat::Bytes.to_bits(&bytes, self.bit_order)
+            }
+            fmt => fmt.to_bits(value.as_bytes(), self.bit_order),
+        }
     }
 
     fn prev_coder(&mut self) {
-        let Some(name) = CoderName::from_repr((self.coder_name as usize).saturating_sub(1)) else {
+        let Some(coder) = self.coder.checked_sub(1) else {
             return;
         };
 
-        self.coder_name = name;
-        self.raw_coder = name.to_raw();
         self.scroll_state.borrow_mut().prev();
         self.list_state.borrow_mut().select_previous();
-        self.parse_and_send();
+        self.swap_coder(coder);
     }
 
     fn next_coder(&mut self) {
-        let Some(name) = CoderName::from_repr(self.coder_name as usize + 1) else {
+        let coder = self.coder + 1;
+        if coder >= self.registry.entries().len() {
             return;
-        };
+        }
 
-        self.coder_name = name;
-        self.raw_coder = name.to_raw();
         self.scroll_state.borrow_mut().next();
         self.list_state.borrow_mut().select_next();
+        self.swap_coder(coder);
+    }
+
+    fn swap_coder(&mut self, coder: usize) {
+        self.coder = coder;
+        self.settings.init_state = self.entry().schema().def_init_state();
+        self.select_setting(self.selected_setting());
+        self.rebuild_coder();
+    }
+
+    #[inline]
+    fn selected_setting(&self) -> usize {
+        self.settings_state.borrow().selected().unwrap_or_default()
+    }
+
+    fn select_setting(&self, idx: usize) {
+        let last = Setting::of(self.entry().schema()).count().saturating_sub(1);
+        self.settings_state.borrow_mut().select(Some(idx.min(last)));
+    }
+
+    fn adjust_setting(&mut self, up: bool) {
+        let Some(setting) = Setting::of(self.entry().schema()).nth(self.selected_setting()) else {
+            return;
+        };
+
+        setting.adjust(self, up);
+        self.rebuild_coder();
+    }
+
+    fn rebuild_coder(&mut self) {
+        self.raw_coder = catalog::build(self.entry(), self.block_name, &self.settings);
+        self.encoder = Incremental::new(self.raw_coder.as_ref());
+        self.simulate_ber();
+        self.rebuild_pins();
+        self.parse_and_send();
+    }
+
+    // Pins follow the current settings so every trace shares one time base;
+    // other coders start from their default initial state.
+    fn rebuild_pins(&mut self) {
+        self.pin_coders = self
+            .pinned
+            .iter()
+            .map(|pin| {
+                let entry = &self.registry.entries()[pin.coder];
+                let settings = Settings {
+                    init_state: if pin.coder == self.coder {
+                        self.settings.init_state
+                    } else {
+                        entry.schema().def_init_state()
+                    },
+                    ..self.settings
+                };
+                catalog::build(entry, pin.block_name, &settings)
+            })
+            .collect();
+    }
+
+    #[inline]
+    fn sampler(&self) -> Sampler {
+        Sampler::new()
+            .with_bit_rate(self.settings.bit_rate)
+            .with_shaping(Self::SHAPINGS[self.shaping])
+    }
+
+    fn simulate_ber(&mut self) {
+        let entry = self.entry().clone();
+        let (block_name, settings) = (self.block_name, self.settings);
+        let sampler = self.sampler().with_samples_per_bit(ber::SAMPLES_PER_BIT);
+        self.ber_job.spawn(&self.sig_tx, move |id| {
+            let n = (Self::BER_SNR_MAX / Self::BER_SNR_STEP) as usize;
+            let points = ber::sweep(
+                catalog::build(&entry, block_name, &settings).as_ref(),
+                &sampler,
+                Self::BER_BITS,
+                (0..=n).map(|i| i as f64 * Self::BER_SNR_STEP),
+            );
+            Update::Ber(id, points)
+        });
+    }
+
+    fn next_block(&mut self) {
+        self.block_name = self.block_name.next();
+        self.rebuild_coder();
+    }
+
+    fn toggle_pin(&mut self) {
+        let current = Pin {
+            coder: self.coder,
+            block_name: self.block_name,
+        };
+        match self.pinned.iter().position(|&pin| pin == current) {
+            Some(idx) => _ = self.pinned.remove(idx),
+            None if self.pinned.len() < Self::MAX_PINNED => self.pinned.push(current),
+            None => return,
+        }
+        self.rebuild_pins();
         self.parse_and_send();
     }
 
     fn parse_and_send(&mut self) {
+        let bits = self.read_bits();
+        self.input_bits = bits.as_ref().map(|bits| bits.len());
+        let bits = bits.unwrap_or_default();
+        let sigs = self.encoder.encode(&bits);
+
+        let random_sigs = (bits.len() < Self::MIN_ANALYSIS_BITS).then(|| {
+            self.raw_coder
+                .encode(&XorShift::default().bits(Self::RANDOM_ANALYSIS_BITS))
+        });
+
         self.sig_tx
-            .send(
-                self.raw_coder
-                    .encode(&util::parse_bits(self.bits_input.value()).unwrap_or_default()),
-            )
+            .send(Update::Encoding(Box::new(Encoding {
+                label: catalog::label(self.entry(), self.block_name),
+                pinned: self
+                    .pinned
+                    .iter()
+                    .zip(&self.pin_coders)
+                    .map(|(pin, coder)| Waveform {
+                        label: catalog::label(&self.registry.entries()[pin.coder], pin.block_name),
+                        sigs: coder.encode(&bits),
+                        cells: coder.cells(&bits),
+                    })
+                    .collect(),
+                cells: self.raw_coder.cells(&bits),
+                stats: Stats::new(&sigs, self.settings.bit_rate),
+                sigs,
+                random_sigs,
+                bit_rate: self.settings.bit_rate,
+                channel: self.channel.to_channel(self.settings.bit_rate),
+                sampler: self.sampler(),
+                ber_id: self.ber_job.id(),
+            })))
             .unwrap();
     }
+
+    fn bits_title(&self) -> Line<'_> {
+        let mut spans = vec![Span::raw(" Bits").style(Theme::SUB_TITLE)];
+        if let Some(pattern) = &self.pattern {
+            spans.push(Span::raw(" · ").style(Theme::BORDER_TERNARY));
+            spans.push(Span::raw(pattern.as_ref()).style(Theme::HINT));
+        } else if self.input_format != InputFormat::Binary {
+            spans.push(Span::raw(" · ").style(Theme::BORDER_TERNARY));
+            spans.push(Span::raw(self.input_format.as_ref()).style(Theme::HINT));
+            if self.input_format.has_bit_order() {
+                spans.push(Span::raw(format!(", {}", self.bit_order.as_ref())).style(Theme::HINT));
+            }
+        }
+
+        match self.input_bits {
+            None => {
+                let err = match self.input_format {
+                    InputFormat::Bytes => "unreadable",
+                    _ => "invalid",
+                };
+                spans.push(Span::raw(" · ").style(Theme::BORDER_TERNARY));
+                spans.push(Span::raw(err).style(Theme::ERROR));
+            }
+            Some(len)
+                if len > 0
+                    && (self.pattern.is_some() || self.input_format != InputFormat::Binary) =>
+            {
+                spans.push(Span::raw(" · ").style(Theme::BORDER_TERNARY));
+                spans.push(Span::raw(format!("{len} bits")).style(Theme::TEXT));
+            }
+            _ => {}
+        }
+        spans.push(Span::raw(" "));
+
+        Line::from(spans)
+    }
 }
 
 impl ActiveWidget for Parameters {
+    fn init(&mut self) {
+        self.simulate_ber();
+        self.parse_and_send();
+    }
+
     fn render_ref(&self, ctx: &Ctx<'_>, frame: &mut Frame<'_>, area: Rect) {
         let base = Block::bordered()
             .style(Theme::BORDER_PRIMARY)
@@ -210,39 +716,60 @@ impl ActiveWidget for Parameters {
             );
         frame.render_widget(base, area);
 
-        let ((meth_sty, meth_border), (input_sty, input_border)) =
-            if matches!(self.focus, Focus::Method) {
-                (
-                    (Theme::BORDER_SECONDARY, BorderType::Thick),
-                    (Theme::BORDER_PRIMARY, BorderType::Rounded),
-                )
+        let pane_style = |focus: Focus| {
+            if self.focus == focus {
+                (Theme::BORDER_SECONDARY, BorderType::Thick)
             } else {
-                (
-                    (Theme::BORDER_PRIMARY, BorderType::Rounded),
-                    (Theme::BORDER_SECONDARY, BorderType::Thick),
-                )
-            };
+                (Theme::BORDER_PRIMARY, BorderType::Rounded)
+            }
+        };
+        let (meth_sty, meth_border) = pane_style(Focus::Method);
+        let (input_sty, input_border) = pane_style(Focus::Bits);
+        let (settings_sty, settings_border) = pane_style(Focus::Settings);
 
-        let [left, right] =
-            Layout::horizontal([Constraint::Percentage(20), Constraint::Percentage(80)]).areas(
-                area.inner(Margin {
-                    horizontal: 2,
-                    vertical: 1,
-                }),
-            );
+        let [left, right] = Layout::horizontal([Constraint::Length(28), Constraint::Fill(1)])
+            .areas(area.inner(Margin {
+                horizontal: 2,
+                vertical: 1,
+            }));
 
-        let list =
-            List::new(CoderName::iter().map(|name| {
-                ListItem::new(Line::from(name.as_ref().to_owned())).style(Theme::TEXT)
-            }))
-            .highlight_style(Theme::HIGHLIGHT_ITEM)
-            .highlight_symbol("> ")
-            .block(
-                Block::bordered()
-                    .border_type(meth_border)
-                    .style(meth_sty)
-                    .title(Line::from(" Method ").style(Theme::SUB_TITLE)),
-            );
+        let list = List::new(
+            self.registry
+                .entries()
+                .iter()
+                .enumerate()
+                .map(|(idx, entry)| {
+                    let pinned = self.pinned.contains(&Pin {
+                        coder: idx,
+                        block_name: self.block_name,
+                    });
+                    ListItem::new(Line::from_iter([
+                        Span::raw(entry.name().to_owned()),
+                        Span::raw(if pinned { " *" } else { "" }).style(Theme::WARN),
+                    ]))
+                    .style(Theme::TEXT)
+                }),
+        )
+        .highlight_style(Theme::HIGHLIGHT_ITEM)
+        .highlight_symbol("> ")
+        .block(
+            Block::bordered()
+                .border_type(meth_border)
+                .style(meth_sty)
+                .title(Line::from(" Method ").style(Theme::SUB_TITLE))
+                .title(
+                    Line::from(format!(" {} ", self.block_name.as_ref()))
+                        .style(Theme::HINT)
+                        .right_aligned(),
+                )
+                .title_bottom(if self.pinned.is_empty() {
+                    Line::default()
+                } else {
+                    Line::from(format!(" {} pinned ", self.pinned.len()))
+                        .style(Theme::WARN)
+                        .right_aligned()
+                }),
+        );
         frame.render_stateful_widget(list, left, &mut self.list_state.borrow_mut());
         frame.render_stateful_widget(
             Scrollbar::new(ScrollbarOrientation::VerticalRight).style(meth_sty),
@@ -252,6 +779,34 @@ impl ActiveWidget for Parameters {
 
         let [bits, help] =
             Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(right);
+        let [bits, settings] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(31)]).areas(bits);
+
+        let settings_list = List::new(Setting::of(self.entry().schema()).map(|setting| {
+            ListItem::new(Line::from(format!(
+                "{:<14}{:>12}",
+                setting.as_ref(),
+                setting.value(self)
+            )))
+            .style(Theme::TEXT)
+        }))
+        .highlight_style(if matches!(self.focus, Focus::Settings) {
+            Theme::HIGHLIGHT_ITEM
+        } else {
+            Theme::TEXT
+        })
+        .highlight_symbol("> ")
+        .block(
+            Block::bordered()
+                .border_type(settings_border)
+                .style(settings_sty)
+                .title(Line::from(" Settings ").style(Theme::SUB_TITLE)),
+        );
+        frame.render_stateful_widget(
+            settings_list,
+            settings,
+            &mut self.settings_state.borrow_mut(),
+        );
         let input_rect = Rect::new(bits.x, bits.y + 1, bits.width, area.height / 3).inner(Margin {
             horizontal: 1,
             vertical: 0,
@@ -268,7 +823,7 @@ impl ActiveWidget for Parameters {
         .scroll((0, input_scroll as _))
         .block(
             Block::bordered()
-                .title(Line::from(" Bits ").style(Theme::SUB_TITLE))
+                .title(self.bits_title())
                 .padding(Padding::horizontal(1))
                 .border_type(input_border)
                 .border_style(if matches!(self.mode, Mode::Insert) {
@@ -279,6 +834,22 @@ impl ActiveWidget for Parameters {
         );
         frame.render_widget(bits_input, input_rect);
 
+        let about = Rect::new(
+            input_rect.x + 1,
+            input_rect.bottom(),
+            input_rect.width.saturating_sub(2),
+            bits.bottom().saturating_sub(input_rect.bottom()),
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from_iter([
+                Span::raw(self.entry().name()).style(Theme::SUB_TITLE),
+                Span::raw(" · ").style(Theme::BORDER_TERNARY),
+                Span::raw(self.entry().description()).style(Theme::TEXT),
+            ]))
+            .wrap(Wrap { trim: true }),
+            about,
+        );
+
         if matches!(self.mode, Mode::Insert) {
             let x = self.bits_input.visual_cursor().max(input_scroll) - input_scroll + 2;
             frame.set_cursor_position((input_rect.x + x as u16, input_rect.y + 1));
@@ -290,18 +861,76 @@ impl ActiveWidget for Parameters {
             Span::raw(" | ").style(Theme::BORDER_TERNARY),
         ];
 
-        help_txt.extend(if matches!(self.focus, Focus::Bits) {
-            [
-                Span::raw("<Enter>").style(Theme::HINT),
-                Span::raw(" to toggle insert mode").style(Theme::TEXT),
-            ]
-        } else {
-            [
+        help_txt.extend(match self.focus {
+            Focus::Bits => {
+                let mut hints = vec![
+                    Span::raw("<Enter>").style(Theme::HINT),
+                    Span::raw(" to toggle insert mode").style(Theme::TEXT),
+                    Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                    Span::raw("<f>").style(Theme::HINT),
+                    Span::raw(" to change input format").style(Theme::TEXT),
+                ];
+                if self.input_format.has_bit_order() {
+                    hints.extend([
+                        Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                        Span::raw("<o>").style(Theme::HINT),
+                        Span::raw(" to flip bit order").style(Theme::TEXT),
+                    ]);
+                }
+                hints.extend([
+                    Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                    Span::raw("<g>").style(Theme::HINT),
+                    Span::raw(" to generate pattern").style(Theme::TEXT),
+                ]);
+                if self.pattern.is_some() {
+                    hints.extend([
+                        Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                        Span::raw("<+/->").patch_style(Theme::HINT),
+                        Span::raw(" to change length").style(Theme::TEXT),
+                    ]);
+                }
+                if self.pattern.is_some_and(Pattern::is_prbs) {
+                    hints.extend([
+                        Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                        Span::raw("<r>").style(Theme::HINT),
+                        Span::raw(" to reseed").style(Theme::TEXT),
+                    ]);
+                }
+                hints
+            }
+            Focus::Method => vec![
                 Span::raw("<Up/Down>").patch_style(Theme::HINT),
                 Span::raw(" to select a method").style(Theme::TEXT),
-            ]
+            ],
+            Focus::Settings => vec![
+                Span::raw("<Up/Down>").patch_style(Theme::HINT),
+                Span::raw(" to select").style(Theme::TEXT),
+                Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                Span::raw("<+/->").patch_style(Theme::HINT),
+                Span::raw(" to adjust").style(Theme::TEXT),
+            ],
         });
-        frame.render_widget(Line::from_iter(help_txt).centered(), help);
+        help_txt.extend([
+            Span::raw(" | ").style(Theme::BORDER_TERNARY),
+            Span::raw("<b>").style(Theme::HINT),
+            Span::raw(" to cycle block code").style(Theme::TEXT),
+            Span::raw(" | ").style(Theme::BORDER_TERNARY),
+            Span::raw("<p>").style(Theme::HINT),
+            Span::raw(" to pin method").style(Theme::TEXT),
+        ]);
+        if !self.pinned.is_empty() {
+            help_txt.extend([
+                Span::raw(" | ").style(Theme::BORDER_TERNARY),
+                Span::raw("<c>").style(Theme::HINT),
+                Span::raw(" to clear pins").style(Theme::TEXT),
+            ]);
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from_iter(help_txt))
+                .centered()
+                .wrap(Wrap { trim: true }),
+            help,
+        );
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
@@ -22,5 +22,20 @@ theme! {
     BORDER_SECONDARY = Style::new().fg(Color::LightGreen),
     BORDER_TERNARY   = Style::new().fg(Color::Yellow),
     WAVEFORM         = Style::new().fg(Color::LightCyan),
+    RECEIVED         = Style::new().fg(Color::LightRed),
+    GRID             = Style::new().fg(Color::DarkGray),
     HIGHLIGHT_ITEM   = Style::new().fg(Color::Black).bg(Color::Gray).add_modifier(Modifier::BOLD),
 }
+
+impl Theme {
+    pub const CURSORS: [Style; 2] = [
+        Style::new().fg(Color::Yellow),
+        Style::new().fg(Color::Magenta),
+    ];
+    pub const PINNED: [Style; 4] = [
+        Style::new().fg(Color::LightGreen),
+        Style::new().fg(Color::LightMagenta),
+        Style::new().fg(Color::LightYellow),
+        Style::new().fg(Color::LightBlue),
+    ];
+}
@@ -1,65 +1,431 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    iter,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
     Frame,
-    layout::{Alignment, Rect},
-    style::Stylize,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
     symbols,
     text::{Line, Span},
-    widgets::{Axis, Block, BorderType, Chart, Dataset, GraphType},
+    widgets::{Axis, Block, BorderType, Borders, Chart, Dataset, GraphType, Paragraph},
+};
+use strum_macros::{AsRefStr, FromRepr};
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    time::Instant,
+};
+
+use lcv::{
+    analysis::{
+        self,
+        ber::BerPoint,
+        eye::{self, Eye},
+        psd::{self, Spectrum},
+        sampler::{Sampler, Shaping},
+    },
+    channel::Channel,
+    coder::{BitCell, Mark, SigElement},
+    export::{svg::Svg, vcd::Vcd, wav::Wav},
+    util,
 };
-use tokio::{sync::mpsc::UnboundedReceiver, time::Instant};
 
-use crate::coder::SigElement;
+use super::{ActiveWidget, Analysis, Ctx, Encoding, Job, Update, style::Theme};
 
-use super::{ActiveWidget, Ctx, style::Theme};
+type Points = Box<[(f64, f64)]>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr, FromRepr)]
+enum View {
+    #[default]
+    Waveform,
+    Spectrum,
+    Eye,
+    #[strum(serialize = "BER")]
+    Ber,
+}
+
+impl View {
+    #[inline]
+    fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+}
 
 #[derive(Debug)]
 pub(super) struct Visualizer {
-    sig_elems: Box<[SigElement]>,
+    view: View,
+    encoding: Encoding,
     points: Box<[(f64, f64)]>,
-    start_pos: usize,
-    sig_rx: UnboundedReceiver<Box<[SigElement]>>,
+    spectrum: Spectrum,
+    spectrum_points: Box<[(f64, f64)]>,
+    eye: Eye,
+    eye_points: Box<[(f64, f64)]>,
+    eye_span: usize,
+    received: Box<[(f64, f64)]>,
+    pinned: Box<[Points]>,
+    offset: usize,
+    span: usize,
+    fit: bool,
+    cursors: [Option<usize>; 2],
+    stats: bool,
+    status: Option<String>,
+    ber: Box<[BerPoint]>,
+    analysis_job: Job,
+    sig_tx: UnboundedSender<Update>,
+    sig_rx: UnboundedReceiver<Update>,
 }
 
 impl Visualizer {
-    const MAX_POINTS_TO_RENDER: usize = 35;
+    const DEFAULT_SPAN: usize = 16;
+    const MAX_SPAN: usize = 1 << 14;
+    const SPECTRUM_SPAN: f64 = 0.25;
+    const SAMPLES_PER_BIT: usize = 32;
+    const GRID_DOTS: usize = 64;
+    const MAX_GRID_CELLS: usize = 64;
+    const CURSOR_NAMES: [&str; 2] = ["A", "B"];
+    const PANEL_WIDTH: u16 = 26;
+    const MAX_EXPORTS_PER_SECOND: usize = 100;
+    // Bit rates the WAV export is played at, to stay audible and keep at
+    // least four samples per bit.
+    const MIN_AUDIO_BIT_RATE: f64 = 100.0;
+    const MAX_AUDIO_BIT_RATE: f64 = Wav::DEFAULT_SAMPLE_RATE as f64 / 4.0;
 
-    pub(super) fn new(sig_rx: UnboundedReceiver<Box<[SigElement]>>) -> Self {
+    pub(super) fn new(sig_tx: UnboundedSender<Update>, sig_rx: UnboundedReceiver<Update>) -> Self {
         Self {
-            start_pos: 0,
+            view: Default::default(),
+            offset: 0,
+            span: Self::DEFAULT_SPAN,
+            fit: false,
+            cursors: [None; 2],
+            stats: true,
             points: Default::default(),
-            sig_elems: Default::default(),
+            spectrum: Default::default(),
+            spectrum_points: Default::default(),
+            eye: Default::default(),
+            eye_points: Default::default(),
+            eye_span: 2,
+            received: Default::default(),
+            pinned: Default::default(),
+            encoding: Default::default(),
+            status: None,
+            ber: Default::default(),
+            analysis_job: Default::default(),
+            sig_tx,
             sig_rx,
         }
     }
 
-    fn right(&mut self) {
-        let n_vis = Self::MAX_POINTS_TO_RENDER.min(self.points.len().max(1));
-        let max_start = self.points.len().saturating_sub(n_vis);
-        self.start_pos = (self.start_pos + 1).min(max_start);
+    fn total_bits(&self) -> usize {
+        let end = self.points.last().map(|p| p.0).unwrap_or_default();
+        (end * self.encoding.bit_rate).round() as usize
+    }
+
+    #[inline]
+    fn max_offset(&self) -> usize {
+        self.total_bits().saturating_sub(self.span)
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        self.fit = false;
+        self.offset = self
+            .offset
+            .saturating_add_signed(delta)
+            .min(self.max_offset());
+    }
+
+    fn jump(&mut self, to_end: bool) {
+        self.fit = false;
+        self.offset = if to_end { self.max_offset() } else { 0 };
+    }
+
+    fn zoom(&mut self, zoom_in: bool) {
+        if self.fit {
+            self.fit = false;
+            self.offset = 0;
+            self.span = self.total_bits().max(1).next_power_of_two();
+        }
+
+        let center = self.offset + self.span / 2;
+        self.span = if zoom_in {
+            (self.span / 2).max(1)
+        } else {
+            (self.span * 2).min(Self::MAX_SPAN)
+        };
+        self.offset = center.saturating_sub(self.span / 2).min(self.max_offset());
+    }
+
+    // First and one past the last bit period in view.
+    fn visible_bits(&self) -> (usize, usize) {
+        let total = self.total_bits();
+        if self.fit || total <= self.span {
+            (0, total)
+        } else {
+            let offset = self.offset.min(total - self.span);
+            (offset, offset + self.span)
+        }
+    }
+
+    // Boundary `idx` is the start of element `idx`, or the end of the last one.
+    fn edge(&self, idx: usize) -> Option<f64> {
+        let sigs = &self.encoding.sigs;
+        match sigs.get(idx) {
+            Some(sig) => Some(sig.ti()),
+            None if idx == sigs.len() => sigs.last().map(SigElement::tf),
+            None => None,
+        }
+    }
+
+    fn cycle_cursors(&mut self) {
+        let [(x0, x1), _] = self.find_axis_bounds();
+        let sigs = &self.encoding.sigs;
+        let mid = sigs.partition_point(|sig| sig.ti() < (x0 + x1) / 2.0);
+
+        self.cursors = match self.cursors {
+            _ if sigs.is_empty() => [None; 2],
+            [None, _] => [Some(mid), None],
+            [Some(a), None] => [Some(a), Some((a + 1).min(sigs.len()))],
+            _ => [None; 2],
+        };
+    }
+
+    fn move_cursor(&mut self, which: usize, delta: isize) {
+        let Some(idx) = self.cursors[which] else {
+            return;
+        };
+        let idx = idx
+            .saturating_add_signed(delta)
+            .min(self.encoding.sigs.len());
+        self.cursors[which] = Some(idx);
+
+        let [(x0, x1), _] = self.find_axis_bounds();
+        let Some(t) = self.edge(idx).filter(|t| !(x0..=x1).contains(t)) else {
+            return;
+        };
+        let bit = (t * self.encoding.bit_rate) as usize;
+        self.fit = false;
+        self.offset = bit.saturating_sub(self.span / 2).min(self.max_offset());
+    }
+
+    fn cursor_lines(
+        &self,
+        [x0, x1]: [f64; 2],
+        [y0, y1]: [f64; 2],
+    ) -> Vec<(Style, [(f64, f64); 2])> {
+        self.cursors
+            .iter()
+            .zip(Theme::CURSORS)
+            .filter_map(|(idx, style)| Some((style, self.edge((*idx)?)?)))
+            .filter(|(_, t)| (x0..=x1).contains(t))
+            .map(|(style, t)| (style, [(t, y0), (t, y1)]))
+            .collect()
+    }
+
+    fn cursor_datasets(lines: &[(Style, [(f64, f64); 2])]) -> impl Iterator<Item = Dataset<'_>> {
+        lines.iter().map(|(style, line)| {
+            Dataset::default()
+                .graph_type(GraphType::Line)
+                .marker(symbols::Marker::Braille)
+                .style(*style)
+                .data(line)
+        })
+    }
+
+    fn panel_row(name: &'static str, value: String) -> Line<'static> {
+        Line::from_iter([
+            Span::raw(format!("  {name:<8}")).style(Theme::TEXT),
+            Span::raw(value).style(Theme::HINT),
+        ])
+    }
+
+    fn stats_lines(&self) -> Vec<Line<'static>> {
+        let stats = &self.encoding.stats;
+        let row = Self::panel_row;
+        let (rds_min, rds_max) = stats.rds();
+
+        vec![
+            Line::from(" Statistics").style(Theme::SUB_TITLE),
+            row("mean", util::fmt_si(stats.mean(), "V")),
+            row("RDS min", format!("{rds_min:.2} V·Tb")),
+            row("RDS max", format!("{rds_max:.2} V·Tb")),
+            row("trans", format!("{:.2} / bit", stats.transitions_per_bit())),
+            row("run", format!("{:.2} Tb", stats.longest_run())),
+            row("levels", stats.levels().to_string()),
+            row(
+                "baud",
+                format!(
+                    "{} ({:.2}×)",
+                    util::fmt_si(stats.baud(), "Bd"),
+                    stats.baud_ratio()
+                ),
+            ),
+            Line::default(),
+        ]
+    }
+
+    fn cursor_readout(&self) -> Vec<Line<'static>> {
+        let sigs = &self.encoding.sigs;
+        let cells = &self.encoding.cells;
+        let row = Self::panel_row;
+
+        let mut lines = Vec::new();
+        let mut readouts = Vec::new();
+        for ((idx, name), style) in self
+            .cursors
+            .iter()
+            .zip(Self::CURSOR_NAMES)
+            .zip(Theme::CURSORS)
+        {
+            let Some((idx, t)) = idx.and_then(|idx| Some((idx, self.edge(idx)?))) else {
+                continue;
+            };
+            let lvl = sigs
+                .get(idx)
+                .or(sigs.last())
+                .map(SigElement::lvl)
+                .unwrap_or_default();
+            let cell = cells
+                .partition_point(|cell| cell.tf() <= t)
+                .min(cells.len().saturating_sub(1));
+
+            lines.push(Line::from(format!(" Cursor {name}")).style(style));
+            lines.push(row("t", util::fmt_si(t, "s")));
+            lines.push(row("level", util::fmt_si(lvl, "V")));
+            if let Some(bit) = cells.get(cell) {
+                lines.push(row("bit", format!("#{cell} = {}", bit.bit())));
+            }
+            lines.push(Line::default());
+            readouts.push((t, lvl));
+        }
+
+        if let [(ta, va), (tb, vb)] = readouts[..] {
+            let dt = tb - ta;
+            lines.push(Line::from(" Delta").style(Theme::SUB_TITLE));
+            lines.push(row("Δt", util::fmt_si(dt, "s")));
+            lines.push(row("", format!("{:.2} Tb", dt * self.encoding.bit_rate)));
+            lines.push(row("ΔV", util::fmt_si(vb - va, "V")));
+        }
+
+        lines
+    }
+
+    fn render_panel(&self, frame: &mut Frame<'_>, area: Rect) {
+        let mut lines = Vec::new();
+        if self.stats {
+            lines.extend(self.stats_lines());
+        }
+        lines.extend(self.cursor_readout());
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::new()
+                    .borders(Borders::LEFT)
+                    .border_style(Theme::BORDER_TERNARY),
+            ),
+            area,
+        );
+    }
+
+    // The bits the line coder sent, which a block code makes differ from
+    // the input.
+    fn coded_bits(&self) -> Box<[u8]> {
+        self.encoding.cells.iter().map(BitCell::bit).collect()
+    }
+
+    fn export_vcd(&mut self) {
+        let vcd = Vcd::new(self.encoding.bit_rate.recip())
+            .with_clock(true)
+            .render(&self.encoding.sigs, Some(&self.coded_bits()));
+        self.save("vcd", vcd);
     }
 
-    fn left(&mut self) {
-        self.start_pos = self.start_pos.saturating_sub(1);
+    fn export_svg(&mut self) {
+        let [(x0, x1), _] = self.find_axis_bounds();
+        let svg = Svg::new(self.encoding.bit_rate.recip())
+            .with_title(&self.encoding.label)
+            .with_window(x0, x1)
+            .render(&self.encoding.sigs, Some(&self.coded_bits()));
+        self.save("svg", svg);
     }
 
-    fn create_axis(&self) -> (Axis<'_>, Axis<'_>, [(f64, f64); 2]) {
+    fn export_wav(&mut self) {
+        let rate = self
+            .encoding
+            .bit_rate
+            .clamp(Self::MIN_AUDIO_BIT_RATE, Self::MAX_AUDIO_BIT_RATE);
+        let wav = Wav::new(self.encoding.bit_rate.recip())
+            .with_bit_rate(rate)
+            .with_shaping(self.encoding.sampler.shaping())
+            .render(&self.encoding.sigs);
+        match wav {
+            Ok(wav) => self.save("wav", wav),
+            Err(err) => self.status = Some(format!("Export failed: {err}")),
+        }
+    }
+
+    fn save(&mut self, ext: &str, contents: impl AsRef<[u8]>) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        // Exports made within the same second get a counter instead of
+        // overwriting each other.
+        let mut path = format!("lcv-{secs}.{ext}");
+        let mut written = Err(io::ErrorKind::AlreadyExists.into());
+        for n in 1..=Self::MAX_EXPORTS_PER_SECOND {
+            written = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(contents.as_ref()));
+            match &written {
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    path = format!("lcv-{secs}-{n}.{ext}")
+                }
+                _ => break,
+            }
+        }
+
+        self.status = Some(match written {
+            Ok(()) => {
+                tracing::info!("Waveform exported to {path}");
+                format!("Saved {path}")
+            }
+            Err(err) => {
+                tracing::error!("Failed to export waveform to {path}: {err}");
+                format!("Export failed: {err}")
+            }
+        });
+    }
+
+    fn create_axis(&self) -> (Axis<'_>, Axis<'_>, [(f64, f64); 2], usize) {
         let bounds @ [(x0, x1), (y0, y1)] = self.find_axis_bounds();
+        let (scale, prefix) = util::si_prefix(x1.abs().max(x0.abs()));
+        let first_label = format!("{:.1}", x0 / scale);
+        let first_width = first_label.len();
         let x_axis = Axis::default()
             .title(Line::from_iter([
                 Span::from("Time ").style(Theme::SUB_TITLE),
-                Span::from("(sec)").style(Theme::WARN.italic()),
+                Span::from(format!("({prefix}sec)")).style(Theme::WARN.italic()),
             ]))
             .bounds([x0, x1])
             .style(Theme::BORDER_TERNARY)
             .labels([
-                Line::from(format!("{x0:.1}")).style(Theme::HINT),
-                Line::from(format!("{:.1}", (x0 + x1) / 2.0)).style(Theme::HINT),
-                Line::from(format!("{x1:.1}")).style(Theme::HINT),
+                Line::from(first_label).style(Theme::HINT),
+                Line::from(format!("{:.1}", (x0 + x1) / 2.0 / scale)).style(Theme::HINT),
+                Line::from(format!("{:.1}", x1 / scale)).style(Theme::HINT),
             ]);
 
-        let y_axis = Axis::default()
-            .title(Line::from("Voltage").style(Theme::SUB_TITLE))
+        let y_axis = Self::level_axis(y0, y1).title(Line::from("Voltage").style(Theme::SUB_TITLE));
+
+        (x_axis, y_axis, bounds, first_width)
+    }
+
+    fn level_axis<'a>(y0: f64, y1: f64) -> Axis<'a> {
+        Axis::default()
             .bounds([y0, y1])
             .style(Theme::BORDER_TERNARY)
             .labels(if y0 < 0.0 && y1 > 0.0 {
@@ -74,22 +440,32 @@ impl Visualizer {
                     Line::from("").style(Theme::HINT),
                     Line::from("+V").style(Theme::HINT),
                 ]
-            });
-
-        (x_axis, y_axis, bounds)
+            })
     }
 
     fn find_axis_bounds(&self) -> [(f64, f64); 2] {
-        let total = self.points.len();
-        let n_vis = Self::MAX_POINTS_TO_RENDER.min(total.max(1));
-        let start = self.start_pos.min(total.saturating_sub(n_vis));
-        let slice = &self.points[start.saturating_sub(1)..(start + n_vis).min(total)];
-        let x0 = if total > 0 { self.points[start].0 } else { 0.0 };
-        let x1 = slice.last().map(|p| p.0).unwrap_or(1.0);
+        let (first, last) = self.visible_bits();
+        let tb = self.encoding.bit_rate.recip();
+        let (x0, x1) = if last > first {
+            (first as f64 * tb, last as f64 * tb)
+        } else {
+            (0.0, 1.0)
+        };
 
+        let (y0, y1) = Self::level_bounds(
+            Self::time_window(&self.points, x0, x1)
+                .iter()
+                .chain(self.received_window(x0, x1))
+                .map(|p| p.1),
+        );
+
+        [(x0, x1), (y0, y1)]
+    }
+
+    fn level_bounds(levels: impl Iterator<Item = f64>) -> (f64, f64) {
         let mut y_min = f64::INFINITY;
         let mut y_max = f64::NEG_INFINITY;
-        for &(_, y) in slice {
+        for y in levels {
             y_min = y_min.min(y);
             y_max = y_max.max(y);
         }
@@ -100,7 +476,7 @@ impl Visualizer {
 
         let pad_sym = |m: f64| (m * 0.1).max(1e-6);
         let pad_pos = |span: f64| (span * 0.1).max(1e-6);
-        let (y0, y1) = if y_min < 0.0 && y_max > 0.0 {
+        if y_min < 0.0 && y_max > 0.0 {
             let m = (y_max.abs().max(y_min.abs())).max(1.0)
                 + pad_sym((y_max.abs().max(y_min.abs())).max(1.0));
             (-m, m)
@@ -110,61 +486,531 @@ impl Visualizer {
         } else {
             let top = if y_max <= 0.0 { 1.0 } else { y_max };
             (0.0, top + pad_pos(top.max(0.1)))
-        };
+        }
+    }
 
-        [(x0, x1), (y0, y1)]
+    fn time_window(points: &[(f64, f64)], x0: f64, x1: f64) -> &[(f64, f64)] {
+        let start = points.partition_point(|p| p.0 < x0).saturating_sub(1);
+        let end = (points.partition_point(|p| p.0 <= x1) + 1).min(points.len());
+        &points[start..end.max(start)]
     }
 
-    fn determine_points(sigs: &[SigElement]) -> Box<[(f64, f64)]> {
-        if sigs.is_empty() {
-            return Default::default();
+    fn received_window(&self, x0: f64, x1: f64) -> &[(f64, f64)] {
+        let start = self.received.partition_point(|p| p.0 < x0);
+        let end = self.received.partition_point(|p| p.0 <= x1);
+        &self.received[start..end.max(start)]
+    }
+
+    fn create_spectrum_axis(&self) -> (Axis<'_>, Axis<'_>) {
+        let x1 = self.spectrum_points.last().map(|p| p.0).unwrap_or(1.0);
+        let y1 = self
+            .spectrum_points
+            .iter()
+            .map(|p| p.1)
+            .fold(0.0, f64::max)
+            .max(1e-12)
+            * 1.1;
+        let (x_scale, x_prefix) = util::si_prefix(x1);
+        let (y_scale, y_prefix) = util::si_prefix(y1);
+
+        let x_axis = Axis::default()
+            .title(Line::from_iter([
+                Span::from("Frequency ").style(Theme::SUB_TITLE),
+                Span::from(format!("({x_prefix}Hz)")).style(Theme::WARN.italic()),
+            ]))
+            .bounds([0.0, x1])
+            .style(Theme::BORDER_TERNARY)
+            .labels([
+                Line::from("0").style(Theme::HINT),
+                Line::from(format!("{:.1}", x1 / 2.0 / x_scale)).style(Theme::HINT),
+                Line::from(format!("{:.1}", x1 / x_scale)).style(Theme::HINT),
+            ]);
+
+        let y_axis = Axis::default()
+            .title(Line::from_iter([
+                Span::from("PSD ").style(Theme::SUB_TITLE),
+                Span::from(format!("({y_prefix}V²/Hz)")).style(Theme::WARN.italic()),
+            ]))
+            .bounds([0.0, y1])
+            .style(Theme::BORDER_TERNARY)
+            .labels([
+                Line::from("0").style(Theme::HINT),
+                Line::from(format!("{:.2}", y1 / 2.0 / y_scale)).style(Theme::HINT),
+                Line::from(format!("{:.2}", y1 / y_scale)).style(Theme::HINT),
+            ]);
+
+        (x_axis, y_axis)
+    }
+
+    fn render_waveform(&self, frame: &mut Frame<'_>, area: Rect, block: Block<'_>) {
+        let (x_axis, y_axis, [(x0, x1), (y0, y1)], x_label) = self.create_axis();
+        let slice = Self::time_window(&self.points, x0, x1);
+        let mut hints = vec![
+            Span::raw(" <+/->").style(Theme::HINT),
+            Span::raw(" zoom ").style(Theme::TEXT),
+            Span::raw("<f>").style(Theme::HINT),
+            Span::raw(" fit ").style(Theme::TEXT),
+            Span::raw("<PgUp/PgDn>").style(Theme::HINT),
+            Span::raw(" page ").style(Theme::TEXT),
+            Span::raw("<Home/End>").style(Theme::HINT),
+            Span::raw(" jump ").style(Theme::TEXT),
+            Span::raw("<c>").style(Theme::HINT),
+            Span::raw(" cursors ").style(Theme::TEXT),
+            Span::raw("<i>").style(Theme::HINT),
+            Span::raw(" stats ").style(Theme::TEXT),
+        ];
+        if self.cursors[0].is_some() {
+            hints.extend([
+                Span::raw("<[/]>").style(Theme::HINT),
+                Span::raw(" move A ").style(Theme::TEXT),
+            ]);
+        }
+        if self.cursors[1].is_some() {
+            hints.extend([
+                Span::raw("<{/}>").style(Theme::HINT),
+                Span::raw(" move B ").style(Theme::TEXT),
+            ]);
+        }
+        let block = block.title_bottom(Line::from_iter(hints));
+
+        let zero_line = Self::zero_line(slice, y0, y1);
+        let grid = Self::bit_grid(&self.encoding.cells, [x0, x1], [y0, y1]);
+        let cursors = self.cursor_lines([x0, x1], [y0, y1]);
+        let mut waveform = Dataset::default()
+            .graph_type(GraphType::Line)
+            .marker(symbols::Marker::HalfBlock)
+            .style(Theme::WAVEFORM)
+            .data(slice);
+
+        let mut datasets = vec![Self::grid_guide(&grid), Self::zero_guide(&zero_line)];
+        if self.received.is_empty() {
+            datasets.push(waveform);
+        } else {
+            waveform = waveform.name("Ideal").marker(symbols::Marker::Braille);
+            datasets.push(waveform);
+            datasets.push(
+                Dataset::default()
+                    .name("Received")
+                    .graph_type(GraphType::Line)
+                    .marker(symbols::Marker::Braille)
+                    .style(Theme::RECEIVED)
+                    .data(self.received_window(x0, x1)),
+            );
         }
 
-        let mut points = Vec::with_capacity(sigs.len() * 3);
-        points.push((sigs[0].ti(), sigs[0].lvl()));
-        points.push((sigs[0].tf(), sigs[0].lvl()));
-        for win in sigs.windows(2) {
-            let a = win[0];
-            let b = win[1];
+        datasets.extend(Self::cursor_datasets(&cursors));
 
-            if (b.lvl() - a.lvl()).abs() > f64::EPSILON {
-                points.push((a.tf(), b.lvl()));
+        let mut inner = block.inner(area);
+        frame.render_widget(block, area);
+        if self.stats || self.cursors[0].is_some() {
+            let [chart, panel] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Length(Self::PANEL_WIDTH)])
+                    .areas(inner);
+            self.render_panel(frame, panel);
+            inner = chart;
+        }
+
+        if self.pinned.is_empty() {
+            let [labels, chart] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+            Self::render_bit_labels(frame, labels, &self.encoding.cells, [x0, x1], x_label);
+            frame.render_widget(Chart::new(datasets).x_axis(x_axis).y_axis(y_axis), chart);
+            return;
+        }
+
+        let rows = Layout::vertical(iter::repeat_n(Constraint::Fill(1), self.pinned.len() + 1))
+            .split(inner);
+        let track = |row: Rect| {
+            Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+            ])
+            .areas::<3>(row)
+        };
+        let bare_axis = || {
+            Axis::default()
+                .bounds([x0, x1])
+                .style(Theme::BORDER_TERNARY)
+        };
+
+        let [title, labels, chart] = track(rows[0]);
+        frame.render_widget(
+            Line::from(format!(" {} ", self.encoding.label)).style(Theme::WAVEFORM),
+            title,
+        );
+        Self::render_bit_labels(frame, labels, &self.encoding.cells, [x0, x1], 0);
+        frame.render_widget(
+            Chart::new(datasets)
+                .x_axis(bare_axis())
+                .y_axis(Self::level_axis(y0, y1)),
+            chart,
+        );
+
+        let pinned = self.encoding.pinned.iter().zip(&self.pinned);
+        for (i, ((pin, points), &row)) in pinned.zip(&rows[1..]).enumerate() {
+            let last = i + 1 == self.pinned.len();
+            let window = Self::time_window(points, x0, x1);
+            let (p0, p1) = Self::level_bounds(window.iter().map(|p| p.1));
+            let zero_line = Self::zero_line(window, p0, p1);
+            let grid = Self::bit_grid(&pin.cells, [x0, x1], [p0, p1]);
+            let cursors = self.cursor_lines([x0, x1], [p0, p1]);
+            let style = Theme::PINNED[i % Theme::PINNED.len()];
+            let mut datasets = vec![
+                Self::grid_guide(&grid),
+                Self::zero_guide(&zero_line),
+                Dataset::default()
+                    .graph_type(GraphType::Line)
+                    .marker(symbols::Marker::HalfBlock)
+                    .style(style)
+                    .data(window),
+            ];
+            datasets.extend(Self::cursor_datasets(&cursors));
+
+            let [title, labels, chart] = track(row);
+            frame.render_widget(Line::from(format!(" {} ", pin.label)).style(style), title);
+            Self::render_bit_labels(
+                frame,
+                labels,
+                &pin.cells,
+                [x0, x1],
+                if last { x_label } else { 0 },
+            );
+            frame.render_widget(
+                Chart::new(datasets)
+                    .x_axis(if last { x_axis.clone() } else { bare_axis() })
+                    .y_axis(Self::level_axis(p0, p1)),
+                chart,
+            );
+        }
+    }
+
+    // Mirrors the `Chart` layout, where the level labels take two columns
+    // unless the first time label reaches further left.
+    fn render_bit_labels(
+        frame: &mut Frame<'_>,
+        area: Rect,
+        cells: &[BitCell],
+        [x0, x1]: [f64; 2],
+        x_label: usize,
+    ) {
+        let lead = (x_label.saturating_sub(1) as u16)
+            .max(2)
+            .min(area.width / 3)
+            + 1;
+        let width = area.width.saturating_sub(lead);
+        let cols_per_cell = cells.first().map_or(0.0, |cell| {
+            (cell.tf() - cell.ti()) * width.saturating_sub(1) as f64 / (x1 - x0)
+        });
+        if x1 <= x0 || cols_per_cell < 2.0 {
+            return;
+        }
+
+        let col = |t: f64| area.x + lead + ((t - x0) * (width - 1) as f64 / (x1 - x0)) as u16;
+        let buf = frame.buffer_mut();
+        for cell in cells {
+            let mid = (cell.ti() + cell.tf()) / 2.0;
+            if !(x0..=x1).contains(&mid) {
+                continue;
             }
-            points.push((b.tf(), b.lvl()));
+
+            let span = col(cell.tf().min(x1)) - col(cell.ti().max(x0));
+            let (text, style) = match cell.mark() {
+                Mark::Data => (cell.bit().to_string(), Theme::HINT),
+                Mark::Violation => (format!("{}V", cell.bit()), Theme::WARN),
+                Mark::Balancing => (format!("{}B", cell.bit()), Theme::WARN),
+            };
+            let text = if (span as usize) > text.len() {
+                &text
+            } else {
+                &text[text.len() - 1..]
+            };
+            let x = col(mid).saturating_sub(text.len() as u16 / 2);
+            buf.set_string(x, area.y, text, style);
         }
+    }
 
-        points.into()
+    fn bit_grid(cells: &[BitCell], [x0, x1]: [f64; 2], [y0, y1]: [f64; 2]) -> Points {
+        let edges = cells
+            .first()
+            .map(BitCell::ti)
+            .into_iter()
+            .chain(cells.iter().map(BitCell::tf))
+            .filter(|t| (x0..=x1).contains(t));
+        if edges.clone().count() > Self::MAX_GRID_CELLS {
+            return Default::default();
+        }
+
+        edges
+            .flat_map(|t| {
+                (0..=Self::GRID_DOTS)
+                    .map(move |k| (t, y0 + (y1 - y0) * k as f64 / Self::GRID_DOTS as f64))
+            })
+            .collect()
     }
-}
 
-impl ActiveWidget for Visualizer {
-    fn render_ref(&self, ctx: &Ctx<'_>, frame: &mut Frame<'_>, area: Rect) {
-        let (x_axis, y_axis, [_, (y0, y1)]) = self.create_axis();
-        let total = self.points.len();
-        let n_vis = Self::MAX_POINTS_TO_RENDER.min(total.max(1));
-        let start = self.start_pos.min(total.saturating_sub(n_vis));
-        let start_inclusive = start.saturating_sub(1);
-        let end_exclusive = (start + n_vis).min(total);
-        let slice = &self.points[start_inclusive..end_exclusive];
-
-        let zero_line = if y0 < 0.0 && y1 > 0.0 {
-            slice.iter().map(|(x, _)| (*x, 0.0)).collect::<Box<[_]>>()
+    fn grid_guide(grid: &[(f64, f64)]) -> Dataset<'_> {
+        Dataset::default()
+            .graph_type(GraphType::Scatter)
+            .marker(symbols::Marker::Dot)
+            .style(Theme::GRID)
+            .data(grid)
+    }
+
+    fn zero_line(points: &[(f64, f64)], y0: f64, y1: f64) -> Box<[(f64, f64)]> {
+        if y0 < 0.0 && y1 > 0.0 {
+            points.iter().map(|(x, _)| (*x, 0.0)).collect()
         } else {
             Default::default()
-        };
+        }
+    }
 
-        let zero_guide = Dataset::default()
+    fn zero_guide(zero_line: &[(f64, f64)]) -> Dataset<'_> {
+        Dataset::default()
             .graph_type(GraphType::Line)
             .marker(symbols::Marker::Dot)
             .style(Theme::TEXT)
-            .data(&zero_line);
+            .data(zero_line)
+    }
 
-        let waveform = Dataset::default()
+    fn render_spectrum(&self, frame: &mut Frame<'_>, area: Rect, block: Block<'_>) {
+        let (x_axis, y_axis) = self.create_spectrum_axis();
+        let spectrum = Dataset::default()
             .graph_type(GraphType::Line)
-            .marker(symbols::Marker::HalfBlock)
+            .marker(symbols::Marker::Braille)
             .style(Theme::WAVEFORM)
-            .data(slice);
+            .data(&self.spectrum_points);
 
+        frame.render_widget(
+            Chart::new(vec![spectrum])
+                .x_axis(x_axis)
+                .y_axis(y_axis)
+                .block(block),
+            area,
+        );
+    }
+
+    fn render_eye(&self, frame: &mut Frame<'_>, area: Rect, block: Block<'_>) {
+        let span = self.eye.span() as f64;
+        let (mut y0, mut y1) = self
+            .eye_points
+            .iter()
+            .fold((0.0f64, 0.0f64), |(lo, hi), &(_, y)| (lo.min(y), hi.max(y)));
+        let pad = ((y1 - y0) * 0.1).max(0.1);
+        (y0, y1) = (y0 - pad, y1 + pad);
+
+        let x_axis = Axis::default()
+            .title(Line::from_iter([
+                Span::from("Time ").style(Theme::SUB_TITLE),
+                Span::from("(Tb)").style(Theme::WARN.italic()),
+            ]))
+            .bounds([0.0, span])
+            .style(Theme::BORDER_TERNARY)
+            .labels([
+                Line::from("0").style(Theme::HINT),
+                Line::from(format!("{}", span / 2.0)).style(Theme::HINT),
+                Line::from(format!("{span}")).style(Theme::HINT),
+            ]);
+        let y_axis = Axis::default()
+            .title(Line::from("Voltage").style(Theme::SUB_TITLE))
+            .bounds([y0, y1])
+            .style(Theme::BORDER_TERNARY)
+            .labels([
+                Line::from(format!("{y0:.1}")).style(Theme::HINT),
+                Line::from(format!("{:.1}", (y0 + y1) / 2.0)).style(Theme::HINT),
+                Line::from(format!("{y1:.1}")).style(Theme::HINT),
+            ]);
+
+        let traces = Dataset::default()
+            .graph_type(GraphType::Line)
+            .marker(symbols::Marker::Braille)
+            .style(Theme::WAVEFORM)
+            .data(&self.eye_points);
+
+        let hints = Line::from_iter([
+            Span::raw(" <s>").style(Theme::HINT),
+            Span::raw(" span ").style(Theme::TEXT),
+        ])
+        .right_aligned();
+
+        frame.render_widget(
+            Chart::new(vec![traces])
+                .x_axis(x_axis)
+                .y_axis(y_axis)
+                .block(block.title_bottom(hints)),
+            area,
+        );
+    }
+
+    fn cycle_eye_span(&mut self) {
+        self.eye_span = if self.eye_span == 1 { 2 } else { 1 };
+        self.analyze();
+    }
+
+    // The spectrum, eye and received trace are left to a background job.
+    fn analyze(&mut self) {
+        let sigs = self.encoding.sigs.clone();
+        let analysis_sigs = Box::<[_]>::from(self.encoding.analysis_sigs());
+        let (channel, sampler) = (self.encoding.channel, self.encoding.sampler);
+        let eye_span = self.eye_span;
+        self.analysis_job.spawn(&self.sig_tx, move |id| {
+            let spectrum = psd::psd(
+                &analysis_sigs,
+                &sampler.with_samples_per_bit(psd::SAMPLES_PER_BIT),
+            );
+            let eye = eye::eye(
+                &analysis_sigs,
+                &sampler.with_samples_per_bit(Self::SAMPLES_PER_BIT),
+                &channel,
+                eye_span,
+            );
+            Update::Analysis(
+                id,
+                Box::new(Analysis {
+                    received: Self::determine_received(&sigs, &channel, &sampler),
+                    spectrum_points: Self::determine_spectrum_points(&spectrum),
+                    eye_points: Self::determine_eye_points(&eye),
+                    spectrum,
+                    eye,
+                }),
+            )
+        });
+    }
+
+    // A single dataset renders far faster than one per trace, so every other
+    // trace is reversed and they only join along the plot edges.
+    fn determine_eye_points(eye: &Eye) -> Box<[(f64, f64)]> {
+        eye.traces()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, trace)| {
+                let points = trace.iter().copied();
+                if i % 2 == 0 {
+                    points.collect::<Vec<_>>()
+                } else {
+                    points.rev().collect()
+                }
+            })
+            .collect()
+    }
+
+    fn render_ber(&self, frame: &mut Frame<'_>, area: Rect, block: Block<'_>) {
+        let points = self
+            .ber
+            .iter()
+            .filter(|p| p.errors() > 0)
+            .map(|p| (p.snr_db(), p.ber().log10()))
+            .collect::<Box<[_]>>();
+        let x0 = self.ber.first().map(|p| p.snr_db()).unwrap_or(0.0);
+        let x1 = self.ber.last().map(|p| p.snr_db()).unwrap_or(1.0);
+        let bits = self.ber.first().map(|p| p.bits()).unwrap_or(1);
+        // Even, so the middle label also lands on a power of ten.
+        let y0 = ((bits as f64).recip().log10() / 2.0).floor().min(-1.0) * 2.0;
+
+        let x_axis = Axis::default()
+            .title(Line::from_iter([
+                Span::from("SNR ").style(Theme::SUB_TITLE),
+                Span::from("(dB)").style(Theme::WARN.italic()),
+            ]))
+            .bounds([x0, x1])
+            .style(Theme::BORDER_TERNARY)
+            .labels([
+                Line::from(format!("{x0}")).style(Theme::HINT),
+                Line::from(format!("{}", (x0 + x1) / 2.0)).style(Theme::HINT),
+                Line::from(format!("{x1}")).style(Theme::HINT),
+            ]);
+        let y_axis = Axis::default()
+            .title(Line::from("BER").style(Theme::SUB_TITLE))
+            .bounds([y0, 0.0])
+            .style(Theme::BORDER_TERNARY)
+            .labels([
+                Line::from(format!("1e{y0}")).style(Theme::HINT),
+                Line::from(format!("1e{}", y0 / 2.0)).style(Theme::HINT),
+                Line::from("1").style(Theme::HINT),
+            ]);
+
+        let curve = Dataset::default()
+            .graph_type(GraphType::Line)
+            .marker(symbols::Marker::Braille)
+            .style(Theme::WAVEFORM)
+            .data(&points);
+
+        frame.render_widget(
+            Chart::new(vec![curve])
+                .x_axis(x_axis)
+                .y_axis(y_axis)
+                .block(block),
+            area,
+        );
+    }
+
+    fn determine_received(
+        sigs: &[SigElement],
+        channel: &Channel,
+        sampler: &Sampler,
+    ) -> Box<[(f64, f64)]> {
+        if channel.is_ideal() && sampler.shaping() == Shaping::None {
+            return Default::default();
+        }
+
+        let sampler = sampler.with_samples_per_bit(Self::SAMPLES_PER_BIT);
+        let fs = sampler.fs();
+        let rx = channel.apply(&sampler.sample(sigs), fs);
+        rx.iter()
+            .enumerate()
+            .map(|(k, &y)| ((k as f64 + 0.5) / fs, y))
+            .collect()
+    }
+
+    fn determine_spectrum_points(spec: &Spectrum) -> Box<[(f64, f64)]> {
+        let max_f = spec.freqs().last().copied().unwrap_or_default() * Self::SPECTRUM_SPAN;
+        spec.freqs()
+            .iter()
+            .zip(spec.density())
+            .take_while(|&(&f, _)| f <= max_f)
+            .map(|(&f, &d)| (f, d))
+            .collect()
+    }
+}
+
+impl ActiveWidget for Visualizer {
+    fn render_ref(&self, ctx: &Ctx<'_>, frame: &mut Frame<'_>, area: Rect) {
+        let title = match self.view {
+            View::Waveform => match self.visible_bits() {
+                (first, last) if last > first => format!(
+                    "{} · bits {first}-{last} of {}{}",
+                    self.view.as_ref(),
+                    self.total_bits(),
+                    if self.fit { " · fit" } else { "" }
+                ),
+                _ => self.view.as_ref().to_owned(),
+            },
+            View::Spectrum => format!(
+                "{} · P = {:.2} V²{}",
+                self.view.as_ref(),
+                self.spectrum.power(),
+                if self.encoding.random_sigs.is_some() {
+                    " · random bits"
+                } else {
+                    ""
+                }
+            ),
+            View::Eye => format!(
+                "{} · H = {:.2} V · W = {:.2} Tb{}",
+                self.view.as_ref(),
+                self.eye.height(),
+                self.eye.width(),
+                if self.encoding.random_sigs.is_some() {
+                    " · random bits"
+                } else {
+                    ""
+                }
+            ),
+            View::Ber => match self.ber.first() {
+                Some(p) => format!("{} · AWGN · {} random bits", self.view.as_ref(), p.bits()),
+                None => format!("{} · AWGN · simulating…", self.view.as_ref()),
+            },
+        };
         let block = Block::bordered()
             .style(Theme::BORDER_PRIMARY)
             .border_type(if ctx.mode.is_visualizer() {
@@ -175,19 +1021,29 @@ impl ActiveWidget for Visualizer {
             .title_top(
                 Line::from_iter([
                     Span::raw("[ ").style(Theme::BORDER_PRIMARY),
-                    Span::raw("Waveform").style(Theme::WARN),
+                    Span::raw(title).style(Theme::WARN),
                     Span::raw(" ]").style(Theme::BORDER_PRIMARY),
                 ])
                 .alignment(Alignment::Center),
             );
+        let block = match &self.status {
+            Some(status) => block.title_bottom(
+                Line::from_iter([
+                    Span::raw("[ ").style(Theme::BORDER_PRIMARY),
+                    Span::raw(status.as_str()).style(Theme::TEXT),
+                    Span::raw(" ]").style(Theme::BORDER_PRIMARY),
+                ])
+                .right_aligned(),
+            ),
+            None => block,
+        };
 
-        frame.render_widget(
-            Chart::new(vec![zero_guide, waveform])
-                .x_axis(x_axis)
-                .y_axis(y_axis)
-                .block(block),
-            area,
-        );
+        match self.view {
+            View::Waveform => self.render_waveform(frame, area, block),
+            View::Spectrum => self.render_spectrum(frame, area, block),
+            View::Eye => self.render_eye(frame, area, block),
+            View::Ber => self.render_ber(frame, area, block),
+        }
     }
 
     fn handle_key(&mut self, key: KeyEvent) {
@@ -198,24 +1054,79 @@ impl ActiveWidget for Visualizer {
         } = key
         {
             match code {
-                KeyCode::Left => self.left(),
-                KeyCode::Right => self.right(),
+                KeyCode::Left => self.scroll(-1),
+                KeyCode::Right => self.scroll(1),
+                KeyCode::PageUp => self.scroll(-(self.span as isize)),
+                KeyCode::PageDown => self.scroll(self.span as isize),
+                KeyCode::Home => self.jump(false),
+                KeyCode::End => self.jump(true),
+                KeyCode::Char('+' | '=') => self.zoom(true),
+                KeyCode::Char('-') => self.zoom(false),
+                KeyCode::Char('f') => self.fit = !self.fit,
+                KeyCode::Char('c') => self.cycle_cursors(),
+                KeyCode::Char('i') => self.stats = !self.stats,
+                KeyCode::Char('[') => self.move_cursor(0, -1),
+                KeyCode::Char(']') => self.move_cursor(0, 1),
+                KeyCode::Char('{') => self.move_cursor(1, -1),
+                KeyCode::Char('}') => self.move_cursor(1, 1),
+                KeyCode::Char('v') => self.view = self.view.next(),
+                KeyCode::Char('s') if self.view == View::Eye => self.cycle_eye_span(),
+                KeyCode::Char('e') => self.export_vcd(),
+                KeyCode::Char('p') => self.export_svg(),
+                KeyCode::Char('w') => self.export_wav(),
                 _ => {}
             }
         };
     }
 
     fn tick(&mut self, _: Instant) {
-        let Ok(sigs) = self.sig_rx.try_recv() else {
-            return;
+        let encoding = match self.sig_rx.try_recv() {
+            Ok(Update::Encoding(encoding)) => *encoding,
+            Ok(Update::Ber(id, points)) => {
+                if id == self.encoding.ber_id {
+                    self.ber = points;
+                }
+                return;
+            }
+            Ok(Update::Analysis(id, analysis)) => {
+                if id == self.analysis_job.id() {
+                    let Analysis {
+                        received,
+                        spectrum,
+                        spectrum_points,
+                        eye,
+                        eye_points,
+                    } = *analysis;
+                    self.received = received;
+                    self.spectrum = spectrum;
+                    self.spectrum_points = spectrum_points;
+                    self.eye = eye;
+                    self.eye_points = eye_points;
+                }
+                return;
+            }
+            Err(_) => return,
         };
 
-        self.sig_elems = sigs;
-        self.points = Self::determine_points(&self.sig_elems);
+        if encoding.ber_id != self.encoding.ber_id {
+            self.ber = Default::default();
+        }
+        self.encoding = encoding;
+        self.points = analysis::polyline(&self.encoding.sigs);
+        self.pinned = self
+            .encoding
+            .pinned
+            .iter()
+            .map(|pin| analysis::polyline(&pin.sigs))
+            .collect();
+        self.analyze();
+        self.offset = self.max_offset();
 
-        let total = self.points.len();
-        let n_vis = Self::MAX_POINTS_TO_RENDER.min(total.max(1));
-        let max_start = total.saturating_sub(n_vis);
-        self.start_pos = max_start;
+        let n = self.encoding.sigs.len();
+        self.cursors = if n == 0 {
+            [None; 2]
+        } else {
+            self.cursors.map(|idx| idx.map(|idx| idx.min(n)))
+        };
     }
 }
@@ -1,24 +1,148 @@
 use nom::{
     Parser,
+    bytes::complete::tag_no_case,
     character::complete::one_of,
-    combinator::{all_consuming, map},
+    combinator::{all_consuming, map, opt},
     multi::many1,
+    sequence::preceded,
 };
+use strum::IntoEnumIterator;
+use strum_macros::{AsRefStr, EnumIter, FromRepr};
 
-#[macro_export]
-macro_rules! test_len_case {
-    ($name:ident: $enc:expr => [
-        $(
-            ($in:expr, $out:expr $(,)?)
-        ),+ $(,)?
-    ]) => {
-        #[test]
-        fn $name() {
-            for (seq, exp) in [$(($in, $out)),*] {
-                assert_eq!($enc.encode(&seq).as_ref(), &exp);
+#[cfg(test)]
+pub(crate) mod testing {
+    macro_rules! test_len_case {
+        ($name:ident: $enc:expr => [
+            $(
+                ($in:expr, $out:expr $(,)?)
+            ),+ $(,)?
+        ]) => {
+            #[test]
+            fn $name() {
+                for (seq, exp) in [$(($in, $out)),*] {
+                    assert_eq!($enc.encode(&seq).as_ref(), &exp);
+                }
             }
-        }
-    };
+        };
+    }
+
+    macro_rules! test_round_trip {
+        ($name:ident: $enc:expr) => {
+            #[test]
+            fn $name() {
+                use $crate::coder::LineDecoder;
+
+                let enc = $enc;
+                for seq in $crate::util::random_bit_strings() {
+                    assert_eq!(enc.decode(&enc.encode(&seq)), seq, "{seq:?}");
+                }
+            }
+        };
+    }
+
+    macro_rules! test_stream {
+        ($name:ident: $enc:expr) => {
+            #[test]
+            fn $name() {
+                use $crate::coder::LineCoder;
+
+                let enc = $enc;
+                for seq in $crate::util::random_bit_strings() {
+                    let mut stream = enc.stream();
+                    let mut out = Vec::new();
+                    let mut len = 0;
+                    for chunk in seq.chunks(seq.len() % 7 + 1) {
+                        stream.push(chunk, &mut out);
+                        len += chunk.len();
+
+                        let mut ended = out.clone();
+                        stream.clone().finish(&mut ended);
+                        assert_eq!(ended, enc.encode(&seq[..len]).as_ref(), "{seq:?}");
+                    }
+                }
+            }
+        };
+    }
+
+    pub(crate) use {test_len_case, test_round_trip, test_stream};
+}
+
+#[derive(Debug, Clone)]
+pub struct XorShift(u64);
+
+impl XorShift {
+    pub const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn bits(&mut self, len: usize) -> Box<[u8]> {
+        (0..len).map(|_| (self.next_u64() & 1) as u8).collect()
+    }
+}
+
+impl Default for XorShift {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+pub fn random_bit_strings() -> impl Iterator<Item = Box<[u8]>> {
+    let mut rng = XorShift::default();
+
+    (1..=256)
+        .map(move |len| {
+            let sparse = len % 2 == 0;
+            (0..len)
+                .map(|_| {
+                    let word = if sparse {
+                        rng.next_u64() & rng.next_u64()
+                    } else {
+                        rng.next_u64()
+                    };
+                    (word & 1) as u8
+                })
+                .collect()
+        })
+        .chain([[0; 64].into(), [1; 64].into()])
+}
+
+pub fn si_prefix(val: f64) -> (f64, &'static str) {
+    const PREFIXES: [(f64, &str); 7] = [
+        (1e9, "G"),
+        (1e6, "M"),
+        (1e3, "k"),
+        (1.0, ""),
+        (1e-3, "m"),
+        (1e-6, "µ"),
+        (1e-9, "n"),
+    ];
+
+    PREFIXES
+        .into_iter()
+        .find(|&(scale, _)| val.abs() >= scale)
+        .unwrap_or((1.0, ""))
+}
+
+pub fn fmt_si(val: f64, unit: &str) -> String {
+    let (scale, prefix) = si_prefix(val);
+    format!("{} {prefix}{unit}", (val / scale * 1e3).round() / 1e3)
 }
 
 #[inline]
@@ -32,9 +156,123 @@ pub fn parse_bits(input: &str) -> Option<Box<[u8]>> {
     .ok()
 }
 
+pub fn parse_hex(input: &str) -> Option<Box<[u8]>> {
+    all_consuming(preceded(
+        opt(tag_no_case::<_, _, nom::error::Error<_>>("0x")),
+        many1(map(one_of("0123456789abcdefABCDEF"), |src| {
+            src.to_digit(16).unwrap_or_default() as u8
+        })),
+    ))
+    .parse(input)
+    .map(|(_, nibbles)| {
+        nibbles
+            .into_iter()
+            .flat_map(|nibble| (0..4).rev().map(move |i| (nibble >> i) & 1))
+            .collect()
+    })
+    .ok()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    #[default]
+    MsbFirst,
+    LsbFirst,
+}
+
+impl BitOrder {
+    #[inline]
+    pub fn toggle(self) -> Self {
+        match self {
+            BitOrder::MsbFirst => BitOrder::LsbFirst,
+            BitOrder::LsbFirst => BitOrder::MsbFirst,
+        }
+    }
+}
+
+impl AsRef<str> for BitOrder {
+    fn as_ref(&self) -> &str {
+        match self {
+            BitOrder::MsbFirst => "MSB first",
+            BitOrder::LsbFirst => "LSB first",
+        }
+    }
+}
+
+pub fn bytes_to_bits(bytes: &[u8], order: BitOrder) -> Box<[u8]> {
+    bytes
+        .iter()
+        .flat_map(|&byte| {
+            (0..8).map(move |i| match order {
+                BitOrder::MsbFirst => (byte >> (7 - i)) & 1,
+                BitOrder::LsbFirst => (byte >> i) & 1,
+            })
+        })
+        .collect()
+}
+
+// Binary and hex input ignore whitespace, while text and raw bytes are taken
+// verbatim, UTF-8 text being split into its encoded bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr, EnumIter, FromRepr)]
+pub enum InputFormat {
+    #[default]
+    #[strum(serialize = "binary")]
+    Binary,
+    #[strum(serialize = "hex")]
+    Hex,
+    #[strum(serialize = "text")]
+    Text,
+    #[strum(serialize = "file")]
+    Bytes,
+}
+
+impl InputFormat {
+    pub fn id(self) -> &'static str {
+        match self {
+            InputFormat::Binary => "bin",
+            InputFormat::Hex => "hex",
+            InputFormat::Text => "text",
+            InputFormat::Bytes => "bytes",
+        }
+    }
+
+    #[inline]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::iter().find(|fmt| fmt.id().eq_ignore_ascii_case(id))
+    }
+
+    #[inline]
+    pub fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn has_bit_order(self) -> bool {
+        matches!(self, InputFormat::Text | InputFormat::Bytes)
+    }
+
+    pub fn to_bits(self, src: &[u8], order: BitOrder) -> Option<Box<[u8]>> {
+        let compact = || {
+            String::from_utf8_lossy(src)
+                .split_whitespace()
+                .collect::<String>()
+        };
+        match self {
+            InputFormat::Binary => parse_bits(&compact()),
+            InputFormat::Hex => parse_hex(&compact()),
+            InputFormat::Text | InputFormat::Bytes if src.is_empty() => None,
+            InputFormat::Text => {
+                std::str::from_utf8(src).ok()?;
+                Some(bytes_to_bits(src, order))
+            }
+            InputFormat::Bytes => Some(bytes_to_bits(src, order)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_bits;
+    use super::{BitOrder, InputFormat, bytes_to_bits, fmt_si, parse_bits, parse_hex};
 
     #[test]
     fn test_bits_parser_with_only_bits() {
@@ -51,10 +289,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fmt_si_picks_closest_prefix() {
+        assert_eq!(fmt_si(1.0, "bps"), "1 bps");
+        assert_eq!(fmt_si(2e3, "bps"), "2 kbps");
+        assert_eq!(fmt_si(5e6, "bps"), "5 Mbps");
+        assert_eq!(fmt_si(0.25e-3, "s"), "250 µs");
+    }
+
     #[test]
     fn test_bits_parser_falis_with_no_bits_data() {
         for input in ["", "abc135", "09"] {
             assert!(parse_bits(input).is_none());
         }
     }
+
+    #[test]
+    fn test_hex_parser_expands_nibbles() {
+        assert_eq!(parse_hex("a5").unwrap().as_ref(), &[1, 0, 1, 0, 0, 1, 0, 1]);
+        assert_eq!(parse_hex("0x3").unwrap().as_ref(), &[0, 0, 1, 1]);
+        assert_eq!(parse_hex("0XF").unwrap().as_ref(), &[1, 1, 1, 1]);
+        for input in ["", "0x", "g1", "12 34"] {
+            assert!(parse_hex(input).is_none(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_bytes_to_bits_bit_order() {
+        assert_eq!(
+            bytes_to_bits(b"A", BitOrder::MsbFirst).as_ref(),
+            &[0, 1, 0, 0, 0, 0, 0, 1]
+        );
+        assert_eq!(
+            bytes_to_bits(b"A", BitOrder::LsbFirst).as_ref(),
+            &[1, 0, 0, 0, 0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_input_formats_to_bits() {
+        let msb = BitOrder::MsbFirst;
+        assert_eq!(
+            InputFormat::Binary
+                .to_bits(b"10 1\n", msb)
+                .unwrap()
+                .as_ref(),
+            &[1, 0, 1]
+        );
+        assert_eq!(
+            InputFormat::Hex.to_bits(b"0f f0", msb).unwrap().as_ref(),
+            &[0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            InputFormat::Text
+                .to_bits("é".as_bytes(), msb)
+                .unwrap()
+                .len(),
+            16
+        );
+        assert!(InputFormat::Text.to_bits(&[0xff], msb).is_none());
+        assert_eq!(InputFormat::Bytes.to_bits(&[0xff], msb).unwrap().len(), 8);
+        for fmt in [InputFormat::Binary, InputFormat::Hex, InputFormat::Text] {
+            assert!(fmt.to_bits(b"", msb).is_none());
+            assert_eq!(InputFormat::from_id(fmt.id()), Some(fmt));
+        }
+    }
 }